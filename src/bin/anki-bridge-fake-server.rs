@@ -0,0 +1,63 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+//! A stand-in for Anki with the `AnkiConnect` add-on, which serves an in-memory collection.
+//!
+//! ```text
+//! anki-bridge-fake-server [--bind ADDRESS] [--api-key KEY]
+//! ```
+
+use std::{net::TcpListener, process::ExitCode};
+
+use anki_bridge::fake::{server, FakeAnki};
+
+const USAGE: &str = "usage: anki-bridge-fake-server [--bind ADDRESS] [--api-key KEY]";
+
+fn main() -> ExitCode {
+    let mut address = server::DEFAULT_ADDRESS.to_string();
+    let mut api_key = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--bind", Some(value)) => address = value,
+            ("--api-key", Some(value)) => api_key = Some(value),
+            _ => {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("failed to listen on {address}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let anki = FakeAnki::new();
+    anki.set_api_key(api_key);
+    eprintln!("serving a fake AnkiConnect on http://{address}");
+    server::serve(&listener, &anki)
+}
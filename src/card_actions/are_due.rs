@@ -24,13 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 /// Parameters for the "areDue" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AreDueRequest {
     /// IDs of the cards to check.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
 }
 
 impl AnkiRequest for AreDueRequest {
@@ -38,4 +38,5 @@ impl AnkiRequest for AreDueRequest {
 
     const ACTION: &'static str = "areDue";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
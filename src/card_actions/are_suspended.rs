@@ -24,13 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 /// Parameters for the "areSuspended" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AreSuspendedRequest {
     /// IDs of the cards to check.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
 }
 
 impl AnkiRequest for AreSuspendedRequest {
@@ -38,4 +38,5 @@ impl AnkiRequest for AreSuspendedRequest {
 
     const ACTION: &'static str = "areSuspended";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -26,13 +26,18 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::AnkiRequest;
+use crate::{
+    ids::{CardId, NoteId},
+    scheduling::{CardQueue, CardSchedule, CardType},
+    timestamps::Timestamp,
+    AnkiRequest,
+};
 
 /// Parameters for retrieving information about cards.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CardsInfoRequest {
     /// The list of card IDs.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
 }
 
 /// Represents the information about a card.
@@ -54,20 +59,20 @@ pub struct CardsInfoResponse {
     /// The CSS style applied to the card.
     pub css: String,
     /// The ID of the card.
-    pub card_id: usize,
-    /// The interval of the card.
-    pub interval: usize,
+    pub card_id: CardId,
+    /// The interval of the card, which is negative for seconds and positive for days.
+    pub interval: isize,
     /// The ID of the note that the card belongs to.
-    pub note: usize,
+    pub note: NoteId,
     /// The ordinal value of the card.
     pub ord: usize,
     /// The type of the card.
     #[serde(rename = "type")]
-    pub type_field: usize,
+    pub type_field: CardType,
     /// The queue of the card.
-    pub queue: usize,
-    /// The due date of the card.
-    pub due: usize,
+    pub queue: CardQueue,
+    /// The due of the card, see [`CardsInfoResponse::schedule`] for its meaning.
+    pub due: isize,
     /// The number of repetitions of the card.
     pub reps: usize,
     /// The number of lapses of the card.
@@ -77,6 +82,23 @@ pub struct CardsInfoResponse {
     /// The modification time of the card.
     #[serde(rename = "mod")]
     pub mod_: usize,
+    /// The ease factor of the card in permille.
+    #[serde(default)]
+    pub factor: usize,
+}
+
+impl CardsInfoResponse {
+    /// Interprets the scheduling fields of the card.
+    #[must_use]
+    pub fn schedule(&self) -> CardSchedule {
+        CardSchedule::from(self)
+    }
+
+    /// Returns the modification time, which is stored in seconds.
+    #[must_use]
+    pub fn modified<T: Timestamp>(&self) -> Option<T> {
+        T::from_unix_seconds(self.mod_ as i64)
+    }
 }
 
 /// Represents the facade of a card.
@@ -93,4 +115,5 @@ impl AnkiRequest for CardsInfoRequest {
 
     const ACTION: &'static str = "cardsInfo";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
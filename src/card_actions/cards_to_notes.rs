@@ -24,18 +24,22 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{
+    ids::{CardId, NoteId},
+    AnkiRequest,
+};
 
 /// Parameters for the "`cardsToNotes`" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CardsToNotesRequest {
     /// The card IDs for which to retrieve the corresponding note IDs.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
 }
 
 impl AnkiRequest for CardsToNotesRequest {
-    type Response = Vec<usize>;
+    type Response = Vec<NoteId>;
 
     const ACTION: &'static str = "cardsToNotes";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
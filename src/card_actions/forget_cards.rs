@@ -24,13 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 /// Parameters for forgetting cards.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ForgetCardsRequest {
     /// The list of card IDs to forget.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
 }
 
 impl AnkiRequest for ForgetCardsRequest {
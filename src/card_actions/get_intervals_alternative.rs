@@ -24,13 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 /// Parameters for the "getIntervals" action alternative.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GetIntervalsAlternativeRequest {
     /// IDs of the cards to retrieve intervals for.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
     /// Determines whether to return a complete 2-dimensional array of intervals.
     pub complete: bool,
 }
@@ -40,4 +40,5 @@ impl AnkiRequest for GetIntervalsAlternativeRequest {
 
     const ACTION: &'static str = "getIntervalsAlternative";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -24,14 +24,14 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 /// Parameters for the "`setSpecificValueOfCard`" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SetSpecificValueOfCardRequest {
     /// ID of the card for which to set specific values.
-    pub card: usize,
+    pub card: CardId,
     /// Keys of the specific values to set.
     pub keys: Vec<String>,
     /// New values to set for the corresponding keys.
@@ -24,13 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 /// Parameters for the "suspended" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SuspendedRequest {
     /// ID of the card to check.
-    pub card: usize,
+    pub card: CardId,
 }
 
 impl AnkiRequest for SuspendedRequest {
@@ -38,4 +38,5 @@ impl AnkiRequest for SuspendedRequest {
 
     const ACTION: &'static str = "suspended";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -24,13 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 /// Parameters for the "unsuspend" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UnsuspendRequest {
     /// IDs of the cards to unsuspend.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
 }
 
 impl AnkiRequest for UnsuspendRequest {
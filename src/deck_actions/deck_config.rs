@@ -0,0 +1,206 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{ids::DeckConfigId, timestamps::Timestamp};
+
+/// Configuration options for a deck, which is called preset in Anki.
+///
+/// It is returned by "`getDeckConfig`" and accepted by "`saveDeckConfig`". Fields, which are
+/// unknown to this crate (like the FSRS parameters), are kept in `extra`, so a fetched config can
+/// be edited and saved back without losing settings.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeckConfig {
+    /// Autoplay setting.
+    pub autoplay: bool,
+    /// Bury interday learning setting.
+    pub bury_interday_learning: bool,
+    #[serde(rename = "dyn")]
+    /// Dyn setting.
+    pub dyn_: bool,
+    /// ID of the deck configuration.
+    pub id: DeckConfigId,
+    /// Lapse configuration.
+    pub lapse: DeckConfigLapse,
+    /// Maximum taken cards setting.
+    pub max_taken: usize,
+    #[serde(rename = "mod")]
+    /// Mod setting.
+    pub mod_: usize,
+    /// Name of the deck.
+    pub name: String,
+    /// New card configuration.
+    pub new: DeckConfigNew,
+    /// New gather priority setting.
+    pub new_gather_priority: usize,
+    /// New card mix setting.
+    pub new_mix: usize,
+    /// Minimum number of new cards per day setting.
+    pub new_per_day_minimum: usize,
+    /// New card sort order setting.
+    pub new_sort_order: usize,
+    /// Replay queue setting.
+    pub replayq: bool,
+    /// Review configuration.
+    pub rev: DeckConfigRev,
+    /// Review order setting.
+    pub review_order: usize,
+    /// Timer setting.
+    pub timer: usize,
+    /// Update sequence number.
+    pub usn: isize,
+    /// Fields, which are unknown to this crate.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Configuration options for new cards in a deck.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeckConfigNew {
+    /// Bury setting.
+    pub bury: bool,
+    /// Delays between steps.
+    pub delays: Vec<f64>,
+    /// Initial factor setting.
+    pub initial_factor: usize,
+    /// Intervals between steps.
+    pub ints: Vec<usize>,
+    /// Order setting.
+    pub order: usize,
+    /// Number of new cards per day setting.
+    pub per_day: usize,
+    /// Fields, which are unknown to this crate.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Configuration options for lapsed cards in a deck.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeckConfigLapse {
+    /// Delays between steps.
+    pub delays: Vec<f64>,
+    /// Leech action setting.
+    pub leech_action: usize,
+    /// Number of leech fails setting.
+    pub leech_fails: usize,
+    /// Minimum interval setting.
+    pub min_int: usize,
+    /// Interval multiplier setting.
+    pub mult: f64,
+    /// Fields, which are unknown to this crate.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Configuration options for review cards in a deck.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeckConfigRev {
+    /// Bury setting.
+    pub bury: bool,
+    /// Ease factor for ease 4 setting.
+    pub ease4: f64,
+    /// Interval factor setting.
+    pub ivl_fct: f64,
+    /// Maximum interval setting.
+    pub max_ivl: usize,
+    /// Number of review cards per day setting.
+    pub per_day: usize,
+    /// Hard factor setting.
+    pub hard_factor: f64,
+    /// Fields, which are unknown to this crate.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl DeckConfig {
+    /// Returns the modification time, which is stored in seconds.
+    #[must_use]
+    pub fn modified<T: Timestamp>(&self) -> Option<T> {
+        T::from_unix_seconds(self.mod_ as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip_keeps_unknown_fields() {
+        let json = json!({
+            "id": 1,
+            "name": "Default",
+            "mod": 1_700_000_000,
+            "usn": -1,
+            "dyn": false,
+            "autoplay": true,
+            "replayq": true,
+            "timer": 0,
+            "maxTaken": 60,
+            "buryInterdayLearning": false,
+            "newGatherPriority": 0,
+            "newMix": 0,
+            "newPerDayMinimum": 0,
+            "newSortOrder": 0,
+            "reviewOrder": 0,
+            "desiredRetention": 0.9,
+            "fsrsWeights": [0.4, 0.6, 2.4],
+            "new": {
+                "bury": false,
+                "delays": [1.0, 10.0],
+                "initialFactor": 2500,
+                "ints": [1, 4, 0],
+                "order": 1,
+                "perDay": 20,
+                "separate": true,
+            },
+            "lapse": {
+                "delays": [10.0],
+                "leechAction": 1,
+                "leechFails": 8,
+                "minInt": 1,
+                "mult": 0.0,
+            },
+            "rev": {
+                "bury": false,
+                "ease4": 1.3,
+                "hardFactor": 1.2,
+                "ivlFct": 1.0,
+                "maxIvl": 36500,
+                "perDay": 200,
+                "fuzz": 0.05,
+            },
+        });
+        let config: DeckConfig = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(config.extra["desiredRetention"], 0.9);
+        assert_eq!(config.new.extra["separate"], true);
+        assert_eq!(serde_json::to_value(&config).unwrap(), json);
+    }
+}
@@ -0,0 +1,742 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::{
+    deck_actions::{
+        clone_deck_config_id::CloneDeckConfigIdRequest, create_deck::CreateDeckRequest,
+        deck_config::DeckConfig, deck_names::DeckNamesRequest, deck_tree::DECK_SEPARATOR,
+        get_deck_config::GetDeckConfigRequest, save_deck_config::SaveDeckConfigRequest,
+        set_deck_config_id::SetDeckConfigIdRequest,
+    },
+    ids::DeckConfigId,
+    send, send_async, AnkiRequestable, AsyncAnkiRequestable, Error,
+};
+
+/// The id of the default deck config, which new decks use.
+const DEFAULT_CONFIG: DeckConfigId = DeckConfigId(1);
+
+/// Errors which can occur while planning or applying a [`DeckLayout`].
+#[derive(Debug, Error)]
+pub enum DeckLayoutError {
+    /// A deck config is referenced, which is neither declared nor used in the collection.
+    #[error("the deck config `{0}` is unknown")]
+    UnknownConfig(String),
+
+    /// Several deck configs have the same name, which Anki allows, but a layout can't tell them
+    /// apart.
+    #[error("several deck configs are named `{0}`")]
+    DuplicateConfig(String),
+
+    /// A declared deck config isn't used by any declared deck, so it couldn't be found again
+    /// after it was created.
+    #[error("the deck config `{0}` isn't used by any declared deck")]
+    UnusedConfig(String),
+
+    /// The settings of a deck config don't fit into a [`DeckConfig`].
+    #[error("the settings of the deck config `{config}` are invalid")]
+    InvalidSettings {
+        config: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Anki rejected a change, e.g. because the deck config was removed meanwhile.
+    #[error("anki rejected to {0}")]
+    Rejected(String),
+
+    /// A request failed.
+    #[error(transparent)]
+    Request(#[from] Error),
+}
+
+/// The declared deck hierarchy with the deck configs, which the decks use.
+///
+/// It can be deserialized from any format supported by serde, e.g. YAML:
+///
+/// ```yaml
+/// decks:
+///   - name: Japanese::Vocabulary
+///     config: Japanese
+///   - name: Japanese::Grammar
+/// configs:
+///   - name: Japanese
+///     settings:
+///       new:
+///         perDay: 30
+///       desiredRetention: 0.85
+/// ```
+///
+/// Decks without a config keep theirs, and new decks use the default config. Every declared
+/// config has to be used by a declared deck, because `AnkiConnect` only finds deck configs
+/// through the decks which use them. Deck configs are matched by name, so the names of the
+/// declared configs and of the configs in the collection have to be unique. The settings are merged into the current [`DeckConfig`] in
+/// its JSON form, so only the declared settings are changed. Decks, which aren't declared, are
+/// reported, but never deleted.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeckLayout {
+    /// The declared decks. Their parents are created as well.
+    #[serde(default)]
+    pub decks: Vec<DeckLayoutDeck>,
+    /// The declared deck configs.
+    #[serde(default)]
+    pub configs: Vec<DeckLayoutConfig>,
+}
+
+/// A declared deck.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckLayoutDeck {
+    /// The full name of the deck, e.g. `Japanese::Vocabulary`.
+    pub name: String,
+    /// The name of the deck config, which the deck uses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+}
+
+/// A declared deck config.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeckLayoutConfig {
+    /// The name of the deck config.
+    pub name: String,
+    /// The name of an existing deck config, which a missing deck config is cloned from,
+    /// defaults to the default deck config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clone_from: Option<String>,
+    /// The settings in the JSON form of [`DeckConfig`], e.g. `{"new": {"perDay": 30}}`.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub settings: Map<String, Value>,
+}
+
+/// A single change, which brings the collection in line with the layout.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeckLayoutOperation {
+    /// Creates a missing deck with its parents.
+    CreateDeck(CreateDeckRequest),
+    /// Creates a missing deck config.
+    CloneConfig {
+        name: String,
+        clone_from: DeckConfigId,
+    },
+    /// Lets the decks use the deck config.
+    SetConfig { decks: Vec<String>, config: String },
+    /// Merges the settings into the deck config, which is read from the given deck.
+    UpdateConfig {
+        config: String,
+        deck: String,
+        /// The paths of the changed settings, e.g. `new.perDay`.
+        changes: Vec<String>,
+        settings: Map<String, Value>,
+    },
+}
+
+impl Display for DeckLayoutOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateDeck(request) => write!(f, "create deck `{}`", request.deck),
+            Self::CloneConfig { name, clone_from } => {
+                write!(f, "create deck config `{name}` from {clone_from}")
+            }
+            Self::SetConfig { decks, config } => {
+                write!(f, "use deck config `{config}` for {decks:?}")
+            }
+            Self::UpdateConfig {
+                config, changes, ..
+            } => {
+                write!(f, "update deck config `{config}`: {}", changes.join(", "))
+            }
+        }
+    }
+}
+
+/// The ordered operations, which bring the collection in line with a [`DeckLayout`].
+///
+/// Its [`Display`] lists one operation per line and the decks, which aren't declared, so it can
+/// be shown as a dry run or as a drift report.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DeckLayoutPlan {
+    /// The operations in the order in which they have to be applied.
+    pub operations: Vec<DeckLayoutOperation>,
+    /// The existing decks, which aren't declared by the layout, nor parents of declared decks.
+    pub unmanaged_decks: Vec<String>,
+    /// The ids of the existing deck configs keyed by name.
+    configs: HashMap<String, DeckConfigId>,
+}
+
+impl DeckLayoutPlan {
+    /// Returns whether the collection is in line with the layout.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Applies the operations in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first operation, which failed. The former operations stay
+    /// applied.
+    pub fn apply<C: DeckLayoutClient>(&self, client: &C) -> Result<(), DeckLayoutError> {
+        let mut configs = self.configs.clone();
+        for operation in &self.operations {
+            match operation {
+                DeckLayoutOperation::CreateDeck(request) => {
+                    send(client, request.clone())?;
+                }
+                DeckLayoutOperation::CloneConfig { name, clone_from } => {
+                    let request = clone_request(name, *clone_from);
+                    configs.insert(name.clone(), send(client, request)?);
+                }
+                DeckLayoutOperation::SetConfig { decks, config } => {
+                    let request = set_request(decks, config, &configs)?;
+                    if !send(client, request)? {
+                        return Err(DeckLayoutError::Rejected(operation.to_string()));
+                    }
+                }
+                DeckLayoutOperation::UpdateConfig {
+                    config,
+                    deck,
+                    settings,
+                    ..
+                } => {
+                    let current = send(client, GetDeckConfigRequest { deck: deck.clone() })?;
+                    let (merged, changes) = merge(config, &current, settings)?;
+                    if !changes.is_empty()
+                        && !send(client, SaveDeckConfigRequest { config: merged })?
+                    {
+                        return Err(DeckLayoutError::Rejected(operation.to_string()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the operations in order asynchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first operation, which failed. The former operations stay
+    /// applied.
+    pub async fn apply_async<C: AsyncDeckLayoutClient>(
+        &self,
+        client: &C,
+    ) -> Result<(), DeckLayoutError> {
+        let mut configs = self.configs.clone();
+        for operation in &self.operations {
+            match operation {
+                DeckLayoutOperation::CreateDeck(request) => {
+                    send_async(client, request.clone()).await?;
+                }
+                DeckLayoutOperation::CloneConfig { name, clone_from } => {
+                    let request = clone_request(name, *clone_from);
+                    configs.insert(name.clone(), send_async(client, request).await?);
+                }
+                DeckLayoutOperation::SetConfig { decks, config } => {
+                    let request = set_request(decks, config, &configs)?;
+                    if !send_async(client, request).await? {
+                        return Err(DeckLayoutError::Rejected(operation.to_string()));
+                    }
+                }
+                DeckLayoutOperation::UpdateConfig {
+                    config,
+                    deck,
+                    settings,
+                    ..
+                } => {
+                    let request = GetDeckConfigRequest { deck: deck.clone() };
+                    let current = send_async(client, request).await?;
+                    let (merged, changes) = merge(config, &current, settings)?;
+                    if !changes.is_empty()
+                        && !send_async(client, SaveDeckConfigRequest { config: merged }).await?
+                    {
+                        return Err(DeckLayoutError::Rejected(operation.to_string()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for DeckLayoutPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.operations.is_empty() {
+            writeln!(f, "the decks are in line with the layout")?;
+        } else {
+            writeln!(f, "bring the decks in line with the layout:")?;
+            for operation in &self.operations {
+                writeln!(f, "  {operation}")?;
+            }
+        }
+        if !self.unmanaged_decks.is_empty() {
+            writeln!(f, "unmanaged decks: {:?}", self.unmanaged_decks)?;
+        }
+        Ok(())
+    }
+}
+
+/// The decks and deck configs of a collection, as read from Anki.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DeckLayoutState {
+    /// The ids of the deck configs, which the decks use, keyed by deck name.
+    pub decks: BTreeMap<String, DeckConfigId>,
+    /// The deck configs, which are used by any deck, keyed by id, as their names needn't be
+    /// unique.
+    pub configs: BTreeMap<DeckConfigId, DeckConfig>,
+}
+
+impl DeckLayout {
+    /// Reads the decks and their configs from Anki and plans the changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request failed, the layout references unknown deck configs or
+    /// several deck configs have the same name.
+    pub fn plan<C: DeckLayoutClient>(&self, client: &C) -> Result<DeckLayoutPlan, DeckLayoutError> {
+        let mut state = DeckLayoutState::default();
+        for deck in send(client, DeckNamesRequest)? {
+            let config = send(client, GetDeckConfigRequest { deck: deck.clone() })?;
+            state.decks.insert(deck, config.id);
+            state.configs.insert(config.id, config);
+        }
+        self.plan_for(&state)
+    }
+
+    /// Reads the decks and their configs from Anki asynchronously and plans the changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request failed, the layout references unknown deck configs or
+    /// several deck configs have the same name.
+    pub async fn plan_async<C: AsyncDeckLayoutClient>(
+        &self,
+        client: &C,
+    ) -> Result<DeckLayoutPlan, DeckLayoutError> {
+        let mut state = DeckLayoutState::default();
+        for deck in send_async(client, DeckNamesRequest).await? {
+            let config = send_async(client, GetDeckConfigRequest { deck: deck.clone() }).await?;
+            state.decks.insert(deck, config.id);
+            state.configs.insert(config.id, config);
+        }
+        self.plan_for(&state)
+    }
+
+    /// Plans the changes from the given state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout references unknown deck configs, several deck configs have
+    /// the same name or a deck config has invalid settings.
+    pub fn plan_for(&self, state: &DeckLayoutState) -> Result<DeckLayoutPlan, DeckLayoutError> {
+        let mut plan = DeckLayoutPlan::default();
+        for config in state.configs.values() {
+            if plan
+                .configs
+                .insert(config.name.clone(), config.id)
+                .is_some()
+            {
+                return Err(DeckLayoutError::DuplicateConfig(config.name.clone()));
+            }
+        }
+        for (index, config) in self.configs.iter().enumerate() {
+            if self.configs[..index]
+                .iter()
+                .any(|declared| declared.name == config.name)
+            {
+                return Err(DeckLayoutError::DuplicateConfig(config.name.clone()));
+            }
+        }
+        for deck in &self.decks {
+            if let Some(config) = &deck.config {
+                if !plan.configs.contains_key(config)
+                    && !self.configs.iter().any(|declared| declared.name == *config)
+                {
+                    return Err(DeckLayoutError::UnknownConfig(config.clone()));
+                }
+            }
+        }
+        for config in &self.configs {
+            if !self
+                .decks
+                .iter()
+                .any(|deck| deck.config.as_ref() == Some(&config.name))
+            {
+                return Err(DeckLayoutError::UnusedConfig(config.name.clone()));
+            }
+        }
+
+        for deck in &self.decks {
+            if !state.decks.contains_key(&deck.name) {
+                plan.operations
+                    .push(DeckLayoutOperation::CreateDeck(CreateDeckRequest {
+                        deck: deck.name.clone(),
+                    }));
+            }
+        }
+
+        for config in &self.configs {
+            if plan.configs.contains_key(&config.name) {
+                continue;
+            }
+            let clone_from = match &config.clone_from {
+                Some(name) => *plan
+                    .configs
+                    .get(name)
+                    .ok_or_else(|| DeckLayoutError::UnknownConfig(name.clone()))?,
+                None => DEFAULT_CONFIG,
+            };
+            plan.operations.push(DeckLayoutOperation::CloneConfig {
+                name: config.name.clone(),
+                clone_from,
+            });
+        }
+
+        let mut assignments = BTreeMap::<&str, Vec<String>>::new();
+        for deck in &self.decks {
+            let Some(config) = &deck.config else {
+                continue;
+            };
+            let current = state
+                .decks
+                .get(&deck.name)
+                .copied()
+                .unwrap_or(DEFAULT_CONFIG);
+            if plan.configs.get(config) != Some(&current) {
+                assignments
+                    .entry(config)
+                    .or_default()
+                    .push(deck.name.clone());
+            }
+        }
+        for (config, decks) in assignments {
+            plan.operations.push(DeckLayoutOperation::SetConfig {
+                decks,
+                config: config.to_string(),
+            });
+        }
+
+        for config in self
+            .configs
+            .iter()
+            .filter(|config| !config.settings.is_empty())
+        {
+            let current = plan
+                .configs
+                .get(&config.name)
+                .and_then(|id| state.configs.get(id));
+            let changes = match current {
+                Some(current) => merge(&config.name, current, &config.settings)?.1,
+                None => leaves(&config.settings, ""),
+            };
+            if changes.is_empty() {
+                continue;
+            }
+            let deck = self
+                .decks
+                .iter()
+                .find(|deck| deck.config.as_ref() == Some(&config.name))
+                .map(|deck| &deck.name)
+                .ok_or_else(|| DeckLayoutError::UnusedConfig(config.name.clone()))?;
+            plan.operations.push(DeckLayoutOperation::UpdateConfig {
+                config: config.name.clone(),
+                deck: deck.clone(),
+                changes,
+                settings: config.settings.clone(),
+            });
+        }
+
+        plan.unmanaged_decks = state
+            .decks
+            .keys()
+            .filter(|deck| {
+                !self.decks.iter().any(|declared| {
+                    declared.name == **deck
+                        || declared
+                            .name
+                            .starts_with(&format!("{deck}{}", DECK_SEPARATOR))
+                })
+            })
+            .cloned()
+            .collect();
+        Ok(plan)
+    }
+}
+
+/// A client, which can send every request of a [`DeckLayout`].
+pub trait DeckLayoutClient:
+    AnkiRequestable<DeckNamesRequest>
+    + AnkiRequestable<GetDeckConfigRequest>
+    + AnkiRequestable<CreateDeckRequest>
+    + AnkiRequestable<CloneDeckConfigIdRequest>
+    + AnkiRequestable<SetDeckConfigIdRequest>
+    + AnkiRequestable<SaveDeckConfigRequest>
+{
+}
+
+impl<C> DeckLayoutClient for C where
+    C: AnkiRequestable<DeckNamesRequest>
+        + AnkiRequestable<GetDeckConfigRequest>
+        + AnkiRequestable<CreateDeckRequest>
+        + AnkiRequestable<CloneDeckConfigIdRequest>
+        + AnkiRequestable<SetDeckConfigIdRequest>
+        + AnkiRequestable<SaveDeckConfigRequest>
+{
+}
+
+/// A client, which can send every request of a [`DeckLayout`] asynchronously.
+pub trait AsyncDeckLayoutClient:
+    AsyncAnkiRequestable<DeckNamesRequest>
+    + AsyncAnkiRequestable<GetDeckConfigRequest>
+    + AsyncAnkiRequestable<CreateDeckRequest>
+    + AsyncAnkiRequestable<CloneDeckConfigIdRequest>
+    + AsyncAnkiRequestable<SetDeckConfigIdRequest>
+    + AsyncAnkiRequestable<SaveDeckConfigRequest>
+    + Sync
+{
+}
+
+impl<C> AsyncDeckLayoutClient for C where
+    C: AsyncAnkiRequestable<DeckNamesRequest>
+        + AsyncAnkiRequestable<GetDeckConfigRequest>
+        + AsyncAnkiRequestable<CreateDeckRequest>
+        + AsyncAnkiRequestable<CloneDeckConfigIdRequest>
+        + AsyncAnkiRequestable<SetDeckConfigIdRequest>
+        + AsyncAnkiRequestable<SaveDeckConfigRequest>
+        + Sync
+{
+}
+
+fn clone_request(name: &str, clone_from: DeckConfigId) -> CloneDeckConfigIdRequest {
+    CloneDeckConfigIdRequest {
+        name: name.to_string(),
+        clone_from,
+    }
+}
+
+fn set_request(
+    decks: &[String],
+    config: &str,
+    configs: &HashMap<String, DeckConfigId>,
+) -> Result<SetDeckConfigIdRequest, DeckLayoutError> {
+    let config_id = *configs
+        .get(config)
+        .ok_or_else(|| DeckLayoutError::UnknownConfig(config.to_string()))?;
+    Ok(SetDeckConfigIdRequest {
+        decks: decks.to_vec(),
+        config_id,
+    })
+}
+
+/// Merges the settings into the config and returns it with the paths of the changed settings.
+fn merge(
+    name: &str,
+    config: &DeckConfig,
+    settings: &Map<String, Value>,
+) -> Result<(DeckConfig, Vec<String>), DeckLayoutError> {
+    let invalid = |source| DeckLayoutError::InvalidSettings {
+        config: name.to_string(),
+        source,
+    };
+    let mut value = serde_json::to_value(config).map_err(invalid)?;
+    let mut changes = Vec::new();
+    if let Value::Object(object) = &mut value {
+        merge_object(object, settings, "", &mut changes);
+    }
+    let merged = serde_json::from_value(value).map_err(invalid)?;
+    Ok((merged, changes))
+}
+
+fn merge_object(
+    target: &mut Map<String, Value>,
+    settings: &Map<String, Value>,
+    prefix: &str,
+    changes: &mut Vec<String>,
+) {
+    for (key, value) in settings {
+        let path = format!("{prefix}{key}");
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(target)), Value::Object(settings)) => {
+                merge_object(target, settings, &format!("{path}."), changes);
+            }
+            (Some(current), value) if same(current, value) => {}
+            _ => {
+                target.insert(key.clone(), value.clone());
+                changes.push(path);
+            }
+        }
+    }
+}
+
+/// Compares numbers by value, so `1` and `1.0` are the same setting.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+        }
+        _ => a == b,
+    }
+}
+
+/// Returns the paths of all settings.
+fn leaves(settings: &Map<String, Value>, prefix: &str) -> Vec<String> {
+    settings
+        .iter()
+        .flat_map(|(key, value)| match value {
+            Value::Object(object) => leaves(object, &format!("{prefix}{key}.")),
+            _ => vec![format!("{prefix}{key}")],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fake::FakeAnki;
+
+    #[test]
+    fn test_plan_and_apply_against_fake() {
+        let anki = FakeAnki::new();
+        let layout: DeckLayout = serde_json::from_value(json!({
+            "decks": [
+                { "name": "Japanese::Vocabulary", "config": "Japanese" },
+                { "name": "Japanese::Grammar" },
+                { "name": "Default", "config": "Default" },
+            ],
+            "configs": [
+                { "name": "Japanese", "settings": { "new": { "perDay": 30 }, "desiredRetention": 0.85 } },
+            ],
+        }))
+        .unwrap();
+
+        let plan = layout.plan(&anki).unwrap();
+        assert_eq!(
+            plan.to_string(),
+            "bring the decks in line with the layout:
+  create deck `Japanese::Vocabulary`
+  create deck `Japanese::Grammar`
+  create deck config `Japanese` from 1
+  use deck config `Japanese` for [\"Japanese::Vocabulary\"]
+  update deck config `Japanese`: desiredRetention, new.perDay
+"
+        );
+        plan.apply(&anki).unwrap();
+
+        let plan = layout.plan(&anki).unwrap();
+        assert!(plan.is_empty(), "{plan}");
+        let config = anki
+            .request(GetDeckConfigRequest {
+                deck: "Japanese::Vocabulary".to_string(),
+            })
+            .unwrap();
+        assert_eq!(config.name, "Japanese");
+        assert_eq!(config.new.per_day, 30);
+        assert_eq!(config.extra["desiredRetention"], 0.85);
+
+        anki.request(CreateDeckRequest {
+            deck: "Scratch".to_string(),
+        })
+        .unwrap();
+        let plan = layout.plan(&anki).unwrap();
+        assert_eq!(plan.unmanaged_decks, ["Scratch"]);
+    }
+
+    #[test]
+    fn test_unknown_config() {
+        let layout = DeckLayout {
+            decks: vec![DeckLayoutDeck {
+                name: "Japanese".to_string(),
+                config: Some("Intense".to_string()),
+            }],
+            configs: Vec::new(),
+        };
+        let error = layout.plan_for(&DeckLayoutState::default()).unwrap_err();
+        assert!(matches!(error, DeckLayoutError::UnknownConfig(name) if name == "Intense"));
+    }
+
+    #[test]
+    fn test_unused_config() {
+        let anki = FakeAnki::new();
+        let layout: DeckLayout = serde_json::from_value(json!({
+            "decks": [{ "name": "Japanese", "config": "Japanese" }],
+            "configs": [{ "name": "Japanese" }, { "name": "Spare" }],
+        }))
+        .unwrap();
+        let error = layout.plan(&anki).unwrap_err();
+        assert!(matches!(error, DeckLayoutError::UnusedConfig(name) if name == "Spare"));
+
+        let layout = DeckLayout {
+            configs: layout.configs[..1].to_vec(),
+            ..layout
+        };
+        layout.plan(&anki).unwrap().apply(&anki).unwrap();
+        let plan = layout.plan(&anki).unwrap();
+        assert!(plan.is_empty(), "{plan}");
+    }
+
+    #[test]
+    fn test_duplicate_config() {
+        let anki = FakeAnki::new();
+        for deck in ["Japanese", "Korean"] {
+            let config_id = anki
+                .request(CloneDeckConfigIdRequest {
+                    name: "Languages".to_string(),
+                    clone_from: DEFAULT_CONFIG,
+                })
+                .unwrap();
+            anki.request(CreateDeckRequest {
+                deck: deck.to_string(),
+            })
+            .unwrap();
+            anki.request(SetDeckConfigIdRequest {
+                decks: vec![deck.to_string()],
+                config_id,
+            })
+            .unwrap();
+        }
+        let layout: DeckLayout = serde_json::from_value(json!({
+            "decks": [{ "name": "Japanese", "config": "Languages" }],
+        }))
+        .unwrap();
+        let error = layout.plan(&anki).unwrap_err();
+        assert!(matches!(error, DeckLayoutError::DuplicateConfig(name) if name == "Languages"));
+
+        let layout: DeckLayout = serde_json::from_value(json!({
+            "decks": [{ "name": "Japanese", "config": "Intense" }],
+            "configs": [{ "name": "Intense" }, { "name": "Intense" }],
+        }))
+        .unwrap();
+        let error = layout.plan_for(&DeckLayoutState::default()).unwrap_err();
+        assert!(matches!(error, DeckLayoutError::DuplicateConfig(name) if name == "Intense"));
+    }
+}
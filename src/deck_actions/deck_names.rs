@@ -35,4 +35,5 @@ impl AnkiRequest for DeckNamesRequest {
 
     const ACTION: &'static str = "deckNames";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -0,0 +1,802 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+use std::{
+    collections::HashMap,
+    ops::{Add, AddAssign},
+};
+
+use thiserror::Error;
+
+use crate::{
+    card_actions::find_cards::FindCardsRequest,
+    deck_actions::{
+        change_deck::ChangeDeckRequest,
+        create_deck::CreateDeckRequest,
+        deck_name_and_ids::DeckNamesAndIdsRequest,
+        delete_decks::DeleteDecksRequest,
+        get_deck_config::GetDeckConfigRequest,
+        get_deck_stats::{GetDeckStatsRequest, GetDeckStatsResponse},
+        get_decks::GetDecksRequest,
+        set_deck_config_id::SetDeckConfigIdRequest,
+    },
+    ids::{CardId, DeckConfigId, DeckId},
+    search::Query,
+    send, send_async, AnkiRequestable, AsyncAnkiRequestable, Error,
+};
+
+/// The separator between the names of a parent deck and its child.
+pub const DECK_SEPARATOR: &str = "::";
+
+/// The id of the default deck, which can't be deleted.
+const DEFAULT_DECK: DeckId = DeckId(1);
+
+/// Errors which can occur while changing a [`DeckTree`].
+#[derive(Debug, Error)]
+pub enum DeckTreeError {
+    /// The deck doesn't exist.
+    #[error("the deck `{0}` doesn't exist")]
+    NotFound(String),
+
+    /// The deck already exists.
+    #[error("the deck `{0}` already exists")]
+    AlreadyExists(String),
+
+    /// The deck would be moved into its own subtree.
+    #[error("the deck `{0}` can't be moved into itself")]
+    IntoItself(String),
+
+    /// The subtree contains the default deck, which can't be deleted.
+    #[error("the deck `{0}` contains the default deck")]
+    DefaultDeck(String),
+
+    /// The subtree still contains cards, which would be deleted.
+    #[error("the deck `{deck}` still contains {cards} cards")]
+    NotEmpty { deck: String, cards: usize },
+
+    /// Cards of the subtree currently sit in a filtered deck outside of it, so they can't be
+    /// moved without taking them out of the filtered deck.
+    #[error("{cards} cards of the deck `{deck}` are in filtered decks")]
+    InFilteredDeck { deck: String, cards: usize },
+
+    /// A request failed.
+    #[error(transparent)]
+    Request(#[from] Error),
+}
+
+/// The numbers of cards in a deck.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeckCounts {
+    /// Number of new cards, which are shown today.
+    pub new: usize,
+    /// Number of cards in the learning phase, which are shown today.
+    pub learn: usize,
+    /// Number of review cards, which are due today.
+    pub review: usize,
+    /// Total number of cards.
+    pub total: usize,
+}
+
+impl Add for DeckCounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            new: self.new + other.new,
+            learn: self.learn + other.learn,
+            review: self.review + other.review,
+            total: self.total + other.total,
+        }
+    }
+}
+
+impl AddAssign for DeckCounts {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl From<&GetDeckStatsResponse> for DeckCounts {
+    fn from(stats: &GetDeckStatsResponse) -> Self {
+        Self {
+            new: stats.new_count,
+            learn: stats.learn_count,
+            review: stats.review_count,
+            total: stats.total_in_deck,
+        }
+    }
+}
+
+/// A deck with its children.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct DeckNode {
+    /// The last component of the name, e.g. `Verbs` for `Japanese::Verbs`.
+    pub name: String,
+    /// The full name, e.g. `Japanese::Verbs`.
+    pub full_name: String,
+    /// The id of the deck, or `None` if only its children were listed.
+    pub id: Option<DeckId>,
+    /// The counts of the deck as returned by "getDeckStats". Its `new`, `learn` and `review`
+    /// counts already include the descendants, only its `total` counts the deck itself.
+    pub own_counts: DeckCounts,
+    /// The counts of the deck and all its descendants.
+    pub counts: DeckCounts,
+    /// The child decks sorted by name.
+    pub children: Vec<DeckNode>,
+}
+
+impl DeckNode {
+    /// Iterates over the deck and all its descendants, parents before their children.
+    pub fn iter(&self) -> impl Iterator<Item = &DeckNode> {
+        let mut nodes = Vec::new();
+        self.collect(&mut nodes);
+        nodes.into_iter()
+    }
+
+    /// Returns the number of parents of the deck.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.full_name.matches(DECK_SEPARATOR).count()
+    }
+
+    fn collect<'a>(&'a self, nodes: &mut Vec<&'a DeckNode>) {
+        nodes.push(self);
+        for child in &self.children {
+            child.collect(nodes);
+        }
+    }
+
+    /// Joins in the stats of the deck and its descendants and returns its counts.
+    fn set_stats(&mut self, stats: &HashMap<DeckId, GetDeckStatsResponse>) -> DeckCounts {
+        let mut descendants = DeckCounts::default();
+        for child in &mut self.children {
+            descendants += child.set_stats(stats);
+        }
+        let own = self.id.and_then(|id| stats.get(&id));
+        self.own_counts = own.map(DeckCounts::from).unwrap_or_default();
+        self.counts = match own {
+            Some(_) => DeckCounts {
+                total: self.own_counts.total + descendants.total,
+                ..self.own_counts
+            },
+            None => descendants,
+        };
+        self.counts
+    }
+
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| a.name.cmp(&b.name));
+        self.children.iter_mut().for_each(Self::sort);
+    }
+}
+
+/// The hierarchy of the decks, which is built from their `Parent::Child` names.
+///
+/// The due counts of [`GetDeckStatsResponse`] already include the subdecks, so only the totals
+/// are summed up to the parents. Decks without stats sum up all counts of their children. The
+/// changing operations refetch the tree, when they are done.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct DeckTree {
+    /// The top-level decks sorted by name.
+    pub roots: Vec<DeckNode>,
+}
+
+impl DeckTree {
+    /// Builds the tree from deck names, e.g. the response of "deckNames".
+    pub fn from_names<S: AsRef<str>>(names: impl IntoIterator<Item = S>) -> Self {
+        let mut tree = Self::default();
+        for name in names {
+            tree.insert(name.as_ref(), None);
+        }
+        tree.finish();
+        tree
+    }
+
+    /// Builds the tree from the response of "deckNamesAndIds".
+    #[must_use]
+    pub fn from_names_and_ids(decks: &HashMap<String, DeckId>) -> Self {
+        let mut tree = Self::default();
+        for (name, id) in decks {
+            tree.insert(name, Some(*id));
+        }
+        tree.finish();
+        tree
+    }
+
+    /// Joins in the response of "getDeckStats" and sums the totals up to the parents.
+    pub fn set_stats(&mut self, stats: &HashMap<DeckId, GetDeckStatsResponse>) {
+        for root in &mut self.roots {
+            root.set_stats(stats);
+        }
+    }
+
+    /// Iterates over all decks, parents before their children.
+    pub fn iter(&self) -> impl Iterator<Item = &DeckNode> {
+        self.roots.iter().flat_map(DeckNode::iter)
+    }
+
+    /// Returns the deck with the given full name.
+    #[must_use]
+    pub fn find(&self, full_name: &str) -> Option<&DeckNode> {
+        let mut parts = full_name.split(DECK_SEPARATOR);
+        let first = parts.next()?;
+        let mut node = self.roots.iter().find(|node| node.name == first)?;
+        for part in parts {
+            node = node.children.iter().find(|node| node.name == part)?;
+        }
+        Some(node)
+    }
+
+    /// Returns the decks of the path, which don't exist yet, parents before their children.
+    #[must_use]
+    pub fn missing_path(&self, path: &str) -> Vec<String> {
+        ancestors(path)
+            .filter(|name| self.find(name).and_then(|node| node.id).is_none())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Fetches the decks with their ids and stats.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request failed.
+    pub fn fetch<C: DeckTreeClient>(client: &C) -> crate::Result<Self> {
+        let decks = send(client, DeckNamesAndIdsRequest)?;
+        let mut tree = Self::from_names_and_ids(&decks);
+        let stats = send(
+            client,
+            GetDeckStatsRequest {
+                decks: decks.into_keys().collect(),
+            },
+        )?;
+        tree.set_stats(&stats);
+        Ok(tree)
+    }
+
+    /// Fetches the decks with their ids and stats asynchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request failed.
+    pub async fn fetch_async<C: AsyncDeckTreeClient>(client: &C) -> crate::Result<Self> {
+        let decks = send_async(client, DeckNamesAndIdsRequest).await?;
+        let mut tree = Self::from_names_and_ids(&decks);
+        let stats = send_async(
+            client,
+            GetDeckStatsRequest {
+                decks: decks.into_keys().collect(),
+            },
+        )
+        .await?;
+        tree.set_stats(&stats);
+        Ok(tree)
+    }
+
+    /// Creates the deck and all its missing parents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request failed.
+    pub fn create_path<C: DeckTreeClient>(
+        &mut self,
+        client: &C,
+        path: &str,
+    ) -> Result<(), DeckTreeError> {
+        for deck in self.missing_path(path) {
+            send(client, CreateDeckRequest { deck })?;
+        }
+        *self = Self::fetch(client)?;
+        Ok(())
+    }
+
+    /// Creates the deck and all its missing parents asynchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request failed.
+    pub async fn create_path_async<C: AsyncDeckTreeClient>(
+        &mut self,
+        client: &C,
+        path: &str,
+    ) -> Result<(), DeckTreeError> {
+        for deck in self.missing_path(path) {
+            send_async(client, CreateDeckRequest { deck }).await?;
+        }
+        *self = Self::fetch_async(client).await?;
+        Ok(())
+    }
+
+    /// Renames the deck with all its descendants by moving their cards to new decks and deleting
+    /// the then empty old decks.
+    ///
+    /// The new decks get the deck configs of the old decks. Cards of the subtree, which currently
+    /// sit in a filtered deck, can't be moved, so the rename is refused until the filtered deck is
+    /// emptied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `old` doesn't exist, `new` already exists or is inside `old`, `old`
+    /// contains the default deck, cards of `old` are in a filtered deck or a request failed.
+    pub fn rename_subtree<C: DeckTreeClient>(
+        &mut self,
+        client: &C,
+        old: &str,
+        new: &str,
+    ) -> Result<(), DeckTreeError> {
+        let decks = self.check_rename(old, new)?;
+        let cards = send(client, FindCardsRequest::from(Query::deck(old)))?;
+        let cards_by_deck = if cards.is_empty() {
+            HashMap::new()
+        } else {
+            send(client, GetDecksRequest { cards })?
+        };
+        let cards_by_deck = moved_cards(old, cards_by_deck, &decks)?;
+        let mut configs = HashMap::<DeckConfigId, Vec<String>>::new();
+        for (deck, renamed) in &decks {
+            let config = send(client, GetDeckConfigRequest { deck: deck.clone() })?;
+            send(
+                client,
+                CreateDeckRequest {
+                    deck: renamed.clone(),
+                },
+            )?;
+            configs.entry(config.id).or_default().push(renamed.clone());
+        }
+        for (config_id, decks) in configs {
+            send(client, SetDeckConfigIdRequest { decks, config_id })?;
+        }
+        for (deck, cards) in cards_by_deck {
+            send(client, ChangeDeckRequest { cards, deck })?;
+        }
+        self.delete_empty(client, old)?;
+        *self = Self::fetch(client)?;
+        Ok(())
+    }
+
+    /// Renames the deck with all its descendants asynchronously, see
+    /// [`DeckTree::rename_subtree`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `old` doesn't exist, `new` already exists or is inside `old`, `old`
+    /// contains the default deck, cards of `old` are in a filtered deck or a request failed.
+    pub async fn rename_subtree_async<C: AsyncDeckTreeClient>(
+        &mut self,
+        client: &C,
+        old: &str,
+        new: &str,
+    ) -> Result<(), DeckTreeError> {
+        let decks = self.check_rename(old, new)?;
+        let cards = send_async(client, FindCardsRequest::from(Query::deck(old))).await?;
+        let cards_by_deck = if cards.is_empty() {
+            HashMap::new()
+        } else {
+            send_async(client, GetDecksRequest { cards }).await?
+        };
+        let cards_by_deck = moved_cards(old, cards_by_deck, &decks)?;
+        let mut configs = HashMap::<DeckConfigId, Vec<String>>::new();
+        for (deck, renamed) in &decks {
+            let config = send_async(client, GetDeckConfigRequest { deck: deck.clone() }).await?;
+            send_async(
+                client,
+                CreateDeckRequest {
+                    deck: renamed.clone(),
+                },
+            )
+            .await?;
+            configs.entry(config.id).or_default().push(renamed.clone());
+        }
+        for (config_id, decks) in configs {
+            send_async(client, SetDeckConfigIdRequest { decks, config_id }).await?;
+        }
+        for (deck, cards) in cards_by_deck {
+            send_async(client, ChangeDeckRequest { cards, deck }).await?;
+        }
+        self.delete_empty_async(client, old).await?;
+        *self = Self::fetch_async(client).await?;
+        Ok(())
+    }
+
+    /// Deletes the deck with all its descendants.
+    ///
+    /// Unless `cards_too` is set, the decks are only deleted if they contain no cards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the deck doesn't exist, contains the default deck or cards, which
+    /// weren't allowed to be deleted, or a request failed.
+    pub fn delete_subtree<C: DeckTreeClient>(
+        &mut self,
+        client: &C,
+        deck: &str,
+        cards_too: bool,
+    ) -> Result<(), DeckTreeError> {
+        self.check_delete(deck)?;
+        if cards_too {
+            send(client, delete_request(deck))?;
+        } else {
+            self.delete_empty(client, deck)?;
+        }
+        *self = Self::fetch(client)?;
+        Ok(())
+    }
+
+    /// Deletes the deck with all its descendants asynchronously, see
+    /// [`DeckTree::delete_subtree`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the deck doesn't exist, contains the default deck or cards, which
+    /// weren't allowed to be deleted, or a request failed.
+    pub async fn delete_subtree_async<C: AsyncDeckTreeClient>(
+        &mut self,
+        client: &C,
+        deck: &str,
+        cards_too: bool,
+    ) -> Result<(), DeckTreeError> {
+        self.check_delete(deck)?;
+        if cards_too {
+            send_async(client, delete_request(deck)).await?;
+        } else {
+            self.delete_empty_async(client, deck).await?;
+        }
+        *self = Self::fetch_async(client).await?;
+        Ok(())
+    }
+
+    /// Returns the full names of the decks, which are renamed, mapped to their new names.
+    fn check_rename(&self, old: &str, new: &str) -> Result<HashMap<String, String>, DeckTreeError> {
+        let node = self.check_delete(old)?;
+        if self.find(new).is_some() {
+            return Err(DeckTreeError::AlreadyExists(new.to_string()));
+        }
+        if new == old || new.starts_with(&format!("{old}{DECK_SEPARATOR}")) {
+            return Err(DeckTreeError::IntoItself(old.to_string()));
+        }
+        Ok(node
+            .iter()
+            .filter_map(|node| {
+                let renamed = renamed(&node.full_name, old, new)?;
+                Some((node.full_name.clone(), renamed))
+            })
+            .collect())
+    }
+
+    fn check_delete(&self, deck: &str) -> Result<&DeckNode, DeckTreeError> {
+        let node = self
+            .find(deck)
+            .ok_or_else(|| DeckTreeError::NotFound(deck.to_string()))?;
+        if node.iter().any(|node| node.id == Some(DEFAULT_DECK)) {
+            return Err(DeckTreeError::DefaultDeck(deck.to_string()));
+        }
+        Ok(node)
+    }
+
+    fn delete_empty<C: DeckTreeClient>(&self, client: &C, deck: &str) -> Result<(), DeckTreeError> {
+        let cards = send(client, FindCardsRequest::from(Query::deck(deck)))?;
+        if !cards.is_empty() {
+            return Err(DeckTreeError::NotEmpty {
+                deck: deck.to_string(),
+                cards: cards.len(),
+            });
+        }
+        send(client, delete_request(deck))?;
+        Ok(())
+    }
+
+    async fn delete_empty_async<C: AsyncDeckTreeClient>(
+        &self,
+        client: &C,
+        deck: &str,
+    ) -> Result<(), DeckTreeError> {
+        let cards = send_async(client, FindCardsRequest::from(Query::deck(deck))).await?;
+        if !cards.is_empty() {
+            return Err(DeckTreeError::NotEmpty {
+                deck: deck.to_string(),
+                cards: cards.len(),
+            });
+        }
+        send_async(client, delete_request(deck)).await?;
+        Ok(())
+    }
+
+    fn insert(&mut self, full_name: &str, id: Option<DeckId>) {
+        let mut nodes = &mut self.roots;
+        for path in ancestors(full_name) {
+            let name = path.rsplit(DECK_SEPARATOR).next().unwrap_or(path);
+            let index = if let Some(index) = nodes.iter().position(|node| node.name == name) {
+                index
+            } else {
+                nodes.push(DeckNode {
+                    name: name.to_string(),
+                    full_name: path.to_string(),
+                    ..Default::default()
+                });
+                nodes.len() - 1
+            };
+            if path.len() == full_name.len() && id.is_some() {
+                nodes[index].id = id;
+            }
+            nodes = &mut nodes[index].children;
+        }
+    }
+
+    fn finish(&mut self) {
+        self.roots.sort_by(|a, b| a.name.cmp(&b.name));
+        self.roots.iter_mut().for_each(DeckNode::sort);
+    }
+}
+
+/// A client, which can send every request of a [`DeckTree`].
+pub trait DeckTreeClient:
+    AnkiRequestable<DeckNamesAndIdsRequest>
+    + AnkiRequestable<GetDeckStatsRequest>
+    + AnkiRequestable<CreateDeckRequest>
+    + AnkiRequestable<FindCardsRequest>
+    + AnkiRequestable<GetDecksRequest>
+    + AnkiRequestable<ChangeDeckRequest>
+    + AnkiRequestable<DeleteDecksRequest>
+    + AnkiRequestable<GetDeckConfigRequest>
+    + AnkiRequestable<SetDeckConfigIdRequest>
+{
+}
+
+impl<C> DeckTreeClient for C where
+    C: AnkiRequestable<DeckNamesAndIdsRequest>
+        + AnkiRequestable<GetDeckStatsRequest>
+        + AnkiRequestable<CreateDeckRequest>
+        + AnkiRequestable<FindCardsRequest>
+        + AnkiRequestable<GetDecksRequest>
+        + AnkiRequestable<ChangeDeckRequest>
+        + AnkiRequestable<DeleteDecksRequest>
+        + AnkiRequestable<GetDeckConfigRequest>
+        + AnkiRequestable<SetDeckConfigIdRequest>
+{
+}
+
+/// A client, which can send every request of a [`DeckTree`] asynchronously.
+pub trait AsyncDeckTreeClient:
+    AsyncAnkiRequestable<DeckNamesAndIdsRequest>
+    + AsyncAnkiRequestable<GetDeckStatsRequest>
+    + AsyncAnkiRequestable<CreateDeckRequest>
+    + AsyncAnkiRequestable<FindCardsRequest>
+    + AsyncAnkiRequestable<GetDecksRequest>
+    + AsyncAnkiRequestable<ChangeDeckRequest>
+    + AsyncAnkiRequestable<DeleteDecksRequest>
+    + AsyncAnkiRequestable<GetDeckConfigRequest>
+    + AsyncAnkiRequestable<SetDeckConfigIdRequest>
+    + Sync
+{
+}
+
+impl<C> AsyncDeckTreeClient for C where
+    C: AsyncAnkiRequestable<DeckNamesAndIdsRequest>
+        + AsyncAnkiRequestable<GetDeckStatsRequest>
+        + AsyncAnkiRequestable<CreateDeckRequest>
+        + AsyncAnkiRequestable<FindCardsRequest>
+        + AsyncAnkiRequestable<GetDecksRequest>
+        + AsyncAnkiRequestable<ChangeDeckRequest>
+        + AsyncAnkiRequestable<DeleteDecksRequest>
+        + AsyncAnkiRequestable<GetDeckConfigRequest>
+        + AsyncAnkiRequestable<SetDeckConfigIdRequest>
+        + Sync
+{
+}
+
+/// Returns the deck and its parents, parents first, e.g. `A`, `A::B` and `A::B::C` for `A::B::C`.
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices(DECK_SEPARATOR)
+        .map(|(index, _)| &path[..index])
+        .chain(std::iter::once(path))
+}
+
+/// Replaces the prefix `old` of a deck in the subtree of `old` with `new`, or returns `None` if the
+/// deck isn't in the subtree.
+fn renamed(deck: &str, old: &str, new: &str) -> Option<String> {
+    let rest = deck.strip_prefix(old)?;
+    (rest.is_empty() || rest.starts_with(DECK_SEPARATOR)).then(|| format!("{new}{rest}"))
+}
+
+/// Maps the cards of the subtree to the decks they are moved to.
+///
+/// A search for the subtree also finds cards, which currently sit in a filtered deck outside of
+/// it. Moving those would take them out of their filtered deck, and their home deck can't be
+/// deleted without deleting them, so the rename is refused before anything is changed.
+fn moved_cards(
+    old: &str,
+    cards_by_deck: HashMap<String, Vec<CardId>>,
+    decks: &HashMap<String, String>,
+) -> Result<Vec<(String, Vec<CardId>)>, DeckTreeError> {
+    let mut filtered = 0;
+    let mut moved = Vec::new();
+    for (deck, cards) in cards_by_deck {
+        match decks.get(&deck) {
+            Some(renamed) => moved.push((renamed.clone(), cards)),
+            None => filtered += cards.len(),
+        }
+    }
+    if filtered > 0 {
+        return Err(DeckTreeError::InFilteredDeck {
+            deck: old.to_string(),
+            cards: filtered,
+        });
+    }
+    Ok(moved)
+}
+
+fn delete_request(deck: &str) -> DeleteDecksRequest {
+    DeleteDecksRequest {
+        decks: vec![deck.to_string()],
+        cards_too: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fake::FakeAnki, mock::MockAnki, prelude::*};
+
+    #[test]
+    fn test_tree_from_names_and_stats() {
+        let decks = HashMap::from([
+            ("Japanese::Verbs".to_string(), DeckId(3)),
+            ("Default".to_string(), DeckId(1)),
+            ("Japanese".to_string(), DeckId(2)),
+            ("Japanese::Kanji::N5".to_string(), DeckId(4)),
+        ]);
+        let mut tree = DeckTree::from_names_and_ids(&decks);
+        // The due counts include the subdecks, the totals don't.
+        let stats = |new_count, total_in_deck| GetDeckStatsResponse {
+            new_count,
+            total_in_deck,
+            ..Default::default()
+        };
+        tree.set_stats(&HashMap::from([
+            (DeckId(2), stats(6, 1)),
+            (DeckId(3), stats(2, 2)),
+            (DeckId(4), stats(3, 3)),
+        ]));
+
+        let names = tree
+            .iter()
+            .map(|node| node.full_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "Default",
+                "Japanese",
+                "Japanese::Kanji",
+                "Japanese::Kanji::N5",
+                "Japanese::Verbs",
+            ]
+        );
+        let japanese = tree.find("Japanese").unwrap();
+        assert_eq!(japanese.counts.new, 6);
+        assert_eq!(japanese.counts.total, 6);
+        assert_eq!(japanese.own_counts.total, 1);
+        let kanji = tree.find("Japanese::Kanji").unwrap();
+        assert_eq!(kanji.id, None);
+        assert_eq!(kanji.counts.new, 3);
+        assert_eq!(kanji.counts.total, 3);
+        assert_eq!(
+            tree.missing_path("Japanese::Kanji::N4"),
+            ["Japanese::Kanji", "Japanese::Kanji::N4"]
+        );
+    }
+
+    #[test]
+    fn test_rename_and_delete_subtree() {
+        let anki = FakeAnki::new();
+        let mut tree = DeckTree::fetch(&anki).unwrap();
+        tree.create_path(&anki, "Japanese::Verbs::Irregular")
+            .unwrap();
+        let note = anki
+            .request(AddNoteRequest {
+                note: AddNoteNote {
+                    deck_name: "Japanese::Verbs".to_string(),
+                    model_name: "Basic".to_string(),
+                    fields: NoteFields::from([("Front", "行く"), ("Back", "to go")]),
+                    ..Default::default()
+                },
+            })
+            .unwrap();
+        let config_id = anki
+            .request(CloneDeckConfigIdRequest {
+                name: "Verbs".to_string(),
+                clone_from: DeckConfigId(1),
+            })
+            .unwrap();
+        anki.request(SetDeckConfigIdRequest {
+            decks: vec!["Japanese::Verbs".to_string()],
+            config_id,
+        })
+        .unwrap();
+
+        tree.rename_subtree(&anki, "Japanese", "日本語").unwrap();
+        assert!(tree.find("Japanese").is_none());
+        assert!(tree.find("日本語::Verbs::Irregular").is_some());
+        assert_eq!(tree.find("日本語").unwrap().counts.total, 1);
+        assert_eq!(tree.find("日本語").unwrap().counts.new, 1);
+        let config = |deck: &str| {
+            anki.request(GetDeckConfigRequest {
+                deck: deck.to_string(),
+            })
+            .unwrap()
+            .id
+        };
+        assert_eq!(config("日本語::Verbs"), config_id);
+        assert_eq!(config("日本語"), DeckConfigId(1));
+
+        let error = tree.delete_subtree(&anki, "日本語", false).unwrap_err();
+        assert!(matches!(error, DeckTreeError::NotEmpty { cards: 1, .. }));
+        tree.delete_subtree(&anki, "日本語", true).unwrap();
+        assert!(tree.find("日本語").is_none());
+        let notes = anki
+            .request(FindNotesRequest::from(Query::note_ids([note])))
+            .unwrap();
+        assert!(notes.0.is_empty());
+
+        let error = tree.delete_subtree(&anki, "Default", true).unwrap_err();
+        assert!(matches!(error, DeckTreeError::DefaultDeck(_)));
+    }
+
+    #[test]
+    fn test_rename_refuses_cards_in_filtered_decks() {
+        let decks = HashMap::from([
+            ("Default".to_string(), DeckId(1)),
+            ("Japanese".to_string(), DeckId(2)),
+            ("Japanese::Verbs".to_string(), DeckId(3)),
+            ("Filtered".to_string(), DeckId(4)),
+        ]);
+        let mut tree = DeckTree::from_names_and_ids(&decks);
+        let anki = MockAnki::new();
+        anki.respond::<FindCardsRequest>(serde_json::json!([10, 11, 12]));
+        // Card 12 belongs to `Japanese`, but currently sits in the filtered deck.
+        anki.respond::<GetDecksRequest>(serde_json::json!({
+            "Japanese::Verbs": [10, 11],
+            "Filtered": [12],
+        }));
+
+        let error = tree
+            .rename_subtree(&anki, "Japanese", "日本語")
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            DeckTreeError::InFilteredDeck { ref deck, cards: 1 } if deck == "Japanese"
+        ));
+        assert!(anki.calls_of::<CreateDeckRequest>().is_empty());
+        assert!(anki.calls_of::<ChangeDeckRequest>().is_empty());
+        assert!(tree.find("Japanese::Verbs").is_some());
+    }
+
+    #[test]
+    fn test_renamed() {
+        assert_eq!(renamed("A::B", "A", "C").as_deref(), Some("C::B"));
+        assert_eq!(renamed("A", "A", "C").as_deref(), Some("C"));
+        assert_eq!(renamed("AB", "A", "C"), None);
+        assert_eq!(renamed("Ä", "A", "C"), None);
+        assert_eq!(renamed("", "A", "C"), None);
+    }
+}
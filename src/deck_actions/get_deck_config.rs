@@ -22,9 +22,12 @@
 * SOFTWARE.
 */
 
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{
+    deck_actions::deck_config::{DeckConfig, DeckConfigLapse, DeckConfigNew, DeckConfigRev},
+    AnkiRequest,
+};
 /// Parameters for the "`getDeckConfig`" action in `AnkiConnect`.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GetDeckConfigRequest {
@@ -32,105 +35,26 @@ pub struct GetDeckConfigRequest {
     pub deck: String,
 }
 
-/// Configuration options for a deck.
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GetDeckConfigResponse {
-    /// Autoplay setting.
-    pub autoplay: bool,
-    /// Bury interday learning setting.
-    pub bury_interday_learning: bool,
-    #[serde(rename = "dyn")]
-    /// Dyn setting.
-    pub dyn_: bool,
-    /// ID of the deck configuration.
-    pub id: usize,
-    /// Lapse configuration.
-    pub lapse: GetDeckConfigLapse,
-    /// Maximum taken cards setting.
-    pub max_taken: usize,
-    #[serde(rename = "mod")]
-    /// Mod setting.
-    pub mod_: usize,
-    /// Name of the deck.
-    pub name: String,
-    /// New card configuration.
-    pub new: GetDeckConfigNew,
-    /// New gather priority setting.
-    pub new_gather_priority: usize,
-    /// New card mix setting.
-    pub new_mix: usize,
-    /// Minimum number of new cards per day setting.
-    pub new_per_day_minimum: usize,
-    /// New card sort order setting.
-    pub new_sort_order: usize,
-    /// Replay queue setting.
-    pub replayq: bool,
-    /// Review configuration.
-    pub rev: GetDeckConfigRev,
-    /// Review order setting.
-    pub review_order: usize,
-    /// Timer setting.
-    pub timer: usize,
-    /// Update sequence number.
-    pub usn: isize,
-}
+/// The configuration returned by "`getDeckConfig`".
+#[deprecated(note = "use `DeckConfig`, which can be saved back losslessly")]
+pub type GetDeckConfigResponse = DeckConfig;
 
-/// Configuration options for new cards in a deck.
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GetDeckConfigNew {
-    /// Bury setting.
-    pub bury: bool,
-    /// Delays between steps.
-    pub delays: Vec<f32>,
-    /// Initial factor setting.
-    pub initial_factor: usize,
-    /// Intervals between steps.
-    pub ints: Vec<usize>,
-    /// Order setting.
-    pub order: usize,
-    /// Number of new cards per day setting.
-    pub per_day: usize,
-}
+/// The settings for new cards returned by "`getDeckConfig`".
+#[deprecated(note = "use `DeckConfigNew`")]
+pub type GetDeckConfigNew = DeckConfigNew;
 
-/// Configuration options for lapsed cards in a deck.
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GetDeckConfigLapse {
-    /// Delays between steps.
-    pub delays: Vec<f32>,
-    /// Leech action setting.
-    pub leech_action: usize,
-    /// Number of leech fails setting.
-    pub leech_fails: usize,
-    /// Minimum interval setting.
-    pub min_int: usize,
-    /// Interval multiplier setting.
-    pub mult: f32,
-}
+/// The settings for lapsed cards returned by "`getDeckConfig`".
+#[deprecated(note = "use `DeckConfigLapse`")]
+pub type GetDeckConfigLapse = DeckConfigLapse;
 
-/// Configuration options for review cards in a deck.
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GetDeckConfigRev {
-    /// Bury setting.
-    pub bury: bool,
-    /// Ease factor for ease 4 setting.
-    pub ease4: f32,
-    /// Interval factor setting.
-    pub ivl_fct: f32,
-    /// Maximum interval setting.
-    pub max_ivl: usize,
-    /// Number of review cards per day setting.
-    pub per_day: usize,
-    /// Hard factor setting.
-    pub hard_factor: f32,
-}
+/// The settings for review cards returned by "`getDeckConfig`".
+#[deprecated(note = "use `DeckConfigRev`")]
+pub type GetDeckConfigRev = DeckConfigRev;
 
 impl AnkiRequest for GetDeckConfigRequest {
-    type Response = GetDeckConfigResponse;
+    type Response = DeckConfig;
 
     const ACTION: &'static str = "getDeckConfig";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -33,12 +33,21 @@ pub mod clone_deck_config_id;
 /// Create a new empty deck. Will not overwrite a deck that exists with the same name.
 pub mod create_deck;
 
+/// The configuration of a deck, which is shared by "`getDeckConfig`" and "`saveDeckConfig`".
+pub mod deck_config;
+
+/// Brings the decks and their configs in line with a declared layout.
+pub mod deck_layout;
+
 /// Gets the complete list of deck names for the current user.
 pub mod deck_name_and_ids;
 
 /// Gets the complete list of deck names for the current user.
 pub mod deck_names;
 
+/// The hierarchy of the decks, which is built from their `Parent::Child` names.
+pub mod deck_tree;
+
 /// Deletes decks with the given names. The argument [`cards_too`](delete_decks::DeleteDecksRequest::cards_too) must be specified and set to true.
 pub mod delete_decks;
 
@@ -0,0 +1,314 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use thiserror::Error;
+
+use crate::ANKI_CONNECT_VERSION;
+
+/// The maximum length of the payload, which is kept in [`Error::Serde`].
+const PAYLOAD_SNIPPET_LENGTH: usize = 256;
+
+/// Represents the possible errors that can occur during the execution of the `anki_connect_send` function.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking"))]
+    /// Error indicating a failure in sending the request with `reqwest`.
+    #[error("send request with reqwest failed")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[cfg(feature = "ureq_blocking")]
+    /// Error indicating a failure in sending the request with `ureq`.
+    #[error("send request with ureq failed")]
+    Ureq(#[from] Box<ureq::Error>),
+
+    /// Error indicating a failure in a custom transport.
+    #[error("send request with the transport failed")]
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    /// Error indicating a failure in reading the response.
+    #[error("reading the response failed")]
+    Io(#[from] std::io::Error),
+
+    /// Error indicating that sending the request of an action failed, e.g. because of the
+    /// transport, before `AnkiConnect` could answer it.
+    #[error("sending the request of {action} failed")]
+    Request {
+        /// The action of the request.
+        action: &'static str,
+        /// The underlying error, e.g. [`Error::Transport`] or [`Error::Io`].
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// Error indicating that the parameters of the request couldn't be serialized.
+    #[error("serialization of the request of {action} failed")]
+    Serialize {
+        /// The action of the request.
+        action: &'static str,
+        /// The underlying error.
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Error indicating a deserialization error.
+    #[error("deserialization of the response of {action} failed")]
+    Serde {
+        /// The action of the request.
+        action: &'static str,
+        /// The beginning of the payload, which couldn't be deserialized.
+        payload: String,
+        /// The underlying error.
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Error indicating that Anki returned an error message.
+    #[error(transparent)]
+    Anki(AnkiError),
+
+    /// Error indicating that the response of a `multi` action has no result for the action in
+    /// the given slot.
+    #[error("the response of the multi action has no result for {action} in slot {index}")]
+    MissingMultiResponse {
+        /// The action of the request in the slot.
+        action: &'static str,
+        /// The position of the request inside the batch.
+        index: usize,
+    },
+
+    /// Error indicating that `AnkiConnect` denied the permission for this origin.
+    #[error("anki denied the permission to use the api")]
+    PermissionDenied,
+
+    /// Error indicating that `AnkiConnect` requires an API key, but the client has none.
+    #[error("anki requires an api key, but none was provided")]
+    ApiKeyRequired,
+
+    /// Error indicating that the version of `AnkiConnect` is older than [`ANKI_CONNECT_VERSION`].
+    #[error("anki connect version {0} is not supported, at least version {ANKI_CONNECT_VERSION} is required")]
+    UnsupportedVersion(usize),
+}
+
+impl Error {
+    /// Creates a [`Error::Serde`], which only keeps the beginning of the payload.
+    pub(crate) fn serde(action: &'static str, payload: &str, source: serde_json::Error) -> Self {
+        let end = payload
+            .char_indices()
+            .nth(PAYLOAD_SNIPPET_LENGTH)
+            .map_or(payload.len(), |(index, _)| index);
+        Self::Serde {
+            action,
+            payload: payload[..end].to_string(),
+            source,
+        }
+    }
+
+    /// Attaches the action to an error, which doesn't know it yet, e.g. an error of the
+    /// transport.
+    #[must_use]
+    pub fn with_action(self, action: &'static str) -> Self {
+        if self.action().is_some() {
+            return self;
+        }
+        Self::Request {
+            action,
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the action of the failed request, if it is known.
+    #[must_use]
+    pub fn action(&self) -> Option<&'static str> {
+        match self {
+            Self::Request { action, .. }
+            | Self::Serialize { action, .. }
+            | Self::Serde { action, .. }
+            | Self::MissingMultiResponse { action, .. } => Some(action),
+            Self::Anki(error) => Some(error.action),
+            _ => None,
+        }
+    }
+
+    /// Returns [true] if the error is likely to go away by itself, e.g. while Anki is starting,
+    /// syncing or switching the profile.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Request { source, .. } => source.is_transient(),
+            #[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking"))]
+            Self::Reqwest(error) => error.is_connect(),
+            #[cfg(feature = "ureq_blocking")]
+            Self::Ureq(error) => matches!(
+                error.as_ref(),
+                ureq::Error::Transport(transport)
+                    if transport.kind() == ureq::ErrorKind::ConnectionFailed
+            ),
+            Self::Io(error) => matches!(
+                error.kind(),
+                std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::ConnectionReset
+            ),
+            Self::Anki(error) => error.kind == AnkiErrorKind::CollectionNotAvailable,
+            _ => false,
+        }
+    }
+
+    /// Returns the kind of the error, if Anki returned an error message.
+    #[must_use]
+    pub fn anki_kind(&self) -> Option<AnkiErrorKind> {
+        match self {
+            Self::Request { source, .. } => source.anki_kind(),
+            Self::Anki(error) => Some(error.kind),
+            _ => None,
+        }
+    }
+}
+
+/// An error message, which was returned by Anki.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("anki returned an error for {action}: {message}")]
+pub struct AnkiError {
+    /// The action of the failed request.
+    pub action: &'static str,
+    /// The kind of the error, parsed from the message.
+    pub kind: AnkiErrorKind,
+    /// The message as returned by Anki.
+    pub message: String,
+}
+
+impl AnkiError {
+    /// Creates the error and parses the kind out of the message.
+    #[must_use]
+    pub fn new(action: &'static str, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self {
+            action,
+            kind: AnkiErrorKind::from_message(&message),
+            message,
+        }
+    }
+}
+
+impl From<AnkiError> for Error {
+    fn from(error: AnkiError) -> Self {
+        Self::Anki(error)
+    }
+}
+
+/// The known kinds of error messages, which are returned by `AnkiConnect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AnkiErrorKind {
+    /// The given deck does not exist.
+    DeckNotFound,
+    /// The given model (note type) does not exist.
+    ModelNotFound,
+    /// The note can't be added, because it is a duplicate.
+    DuplicateNote,
+    /// The collection is not available, e.g. while Anki is syncing or switching the profile.
+    CollectionNotAvailable,
+    /// The request was refused, e.g. because the API key is missing or invalid.
+    PermissionDenied,
+    /// The action is not supported by this version of `AnkiConnect`.
+    UnsupportedAction,
+    /// Any other error message.
+    Other,
+}
+
+impl AnkiErrorKind {
+    /// Parses the kind out of an error message returned by `AnkiConnect`.
+    #[must_use]
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("deck was not found") || message.contains("deck not found") {
+            Self::DeckNotFound
+        } else if message.contains("model was not found") || message.contains("model not found") {
+            Self::ModelNotFound
+        } else if message.contains("duplicate") {
+            Self::DuplicateNote
+        } else if message.contains("collection is not available") {
+            Self::CollectionNotAvailable
+        } else if message.contains("api key") || message.contains("permission") {
+            Self::PermissionDenied
+        } else if message.contains("unsupported action") {
+            Self::UnsupportedAction
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anki_error_kind_from_message() {
+        for (message, kind) in [
+            ("deck was not found: Japanese", AnkiErrorKind::DeckNotFound),
+            ("model was not found: Basic", AnkiErrorKind::ModelNotFound),
+            (
+                "cannot create note because it is a duplicate",
+                AnkiErrorKind::DuplicateNote,
+            ),
+            (
+                "collection is not available",
+                AnkiErrorKind::CollectionNotAvailable,
+            ),
+            (
+                "valid api key must be provided",
+                AnkiErrorKind::PermissionDenied,
+            ),
+            ("unsupported action", AnkiErrorKind::UnsupportedAction),
+            ("something went wrong", AnkiErrorKind::Other),
+        ] {
+            assert_eq!(kind, AnkiErrorKind::from_message(message), "{message}");
+        }
+    }
+
+    #[test]
+    fn test_serde_error_payload_snippet() {
+        let payload = "ä".repeat(PAYLOAD_SNIPPET_LENGTH * 2);
+        let source = serde_json::from_str::<usize>(&payload).unwrap_err();
+        let Error::Serde { payload, .. } = Error::serde("findCards", &payload, source) else {
+            panic!("expected a serde error");
+        };
+        assert_eq!(PAYLOAD_SNIPPET_LENGTH, payload.chars().count());
+    }
+
+    #[test]
+    fn test_with_action() {
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        let error = Error::Io(refused).with_action("findCards");
+        assert_eq!(error.action(), Some("findCards"));
+        assert!(error.is_transient());
+        assert!(
+            matches!(&error, Error::Request { source, .. } if matches!(**source, Error::Io(_)))
+        );
+
+        let error = Error::Anki(AnkiError::new("findCards", "deck was not found: Test"))
+            .with_action("deckNames");
+        assert_eq!(error.action(), Some("findCards"));
+        assert_eq!(error.anki_kind(), Some(AnkiErrorKind::DeckNotFound));
+    }
+}
@@ -0,0 +1,1258 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Map, Value};
+
+use super::{
+    collection::{
+        cloze_numbers, now, Card, Collection, Model, Note, Review, Template, DEFAULT_DECK_ID,
+    },
+    search,
+};
+use crate::ANKI_CONNECT_VERSION;
+
+/// The result of an action, the error is the message which `AnkiConnect` would return.
+pub(crate) type ActionResult = std::result::Result<Value, String>;
+
+/// The actions, which are supported by the fake collection.
+pub(crate) const ACTIONS: &[&str] = &[
+    "addNote",
+    "addNotes",
+    "addTags",
+    "apiReflect",
+    "areDue",
+    "areSuspended",
+    "canAddNotes",
+    "canAddNotesWithErrorDetail",
+    "cardReviews",
+    "cardsInfo",
+    "cardsModTime",
+    "cardsToNotes",
+    "changeDeck",
+    "clearUnusedTags",
+    "cloneDeckConfigId",
+    "createDeck",
+    "createModel",
+    "deckNames",
+    "deckNamesAndIds",
+    "deleteDecks",
+    "deleteNotes",
+    "findCards",
+    "findNotes",
+    "forgetCards",
+    "getDeckConfig",
+    "getDeckStats",
+    "getDecks",
+    "getEaseFactors",
+    "getIntervals",
+    "getLatestReviewID",
+    "getNoteTags",
+    "getNumCardsReviewedByDay",
+    "getNumCardsReviewedToday",
+    "getProfiles",
+    "getReviewsOfCards",
+    "getTags",
+    "insertReviews",
+    "loadProfile",
+    "modelFieldAdd",
+    "modelFieldNames",
+    "modelFieldRemove",
+    "modelFieldRename",
+    "modelFieldReposition",
+    "modelNames",
+    "modelNamesAndIds",
+    "modelStyling",
+    "modelTemplateAdd",
+    "modelTemplateRemove",
+    "modelTemplateRename",
+    "modelTemplates",
+    "multi",
+    "notesInfo",
+    "relearnCards",
+    "reloadCollection",
+    "removeDeckConfigId",
+    "removeTags",
+    "replaceTags",
+    "replaceTagsInAllNotes",
+    "requestPermission",
+    "saveDeckConfig",
+    "setDeckConfigId",
+    "setEaseFactors",
+    "setSpecificValueOfCard",
+    "suspend",
+    "suspended",
+    "sync",
+    "unsuspend",
+    "updateModelStyling",
+    "updateModelTemplates",
+    "updateNote",
+    "updateNoteFields",
+    "updateNoteModel",
+    "updateNoteTags",
+    "version",
+];
+
+/// Reads a parameter, a missing parameter is treated like `null`.
+fn param<T: DeserializeOwned>(params: &Value, name: &str) -> std::result::Result<T, String> {
+    serde_json::from_value(params.get(name).cloned().unwrap_or(Value::Null))
+        .map_err(|error| format!("invalid parameter {name}: {error}"))
+}
+
+fn ok(value: impl serde::Serialize) -> ActionResult {
+    Ok(json!(value))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct NewNote {
+    deck_name: String,
+    model_name: String,
+    fields: HashMap<String, String>,
+    tags: Vec<String>,
+    options: NewNoteOptions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct NewNoteOptions {
+    allow_duplicate: bool,
+    duplicate_scope: Option<String>,
+    duplicate_scope_options: DuplicateScopeOptions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct DuplicateScopeOptions {
+    deck_name: Option<String>,
+    check_children: bool,
+    check_all_models: bool,
+}
+
+impl Collection {
+    /// Executes the action like `AnkiConnect` would do it.
+    pub(crate) fn handle(&mut self, action: &str, params: &Value) -> ActionResult {
+        match action {
+            // miscellaneous actions
+            "version" => ok(ANKI_CONNECT_VERSION),
+            "requestPermission" => ok(json!({
+                "permission": "granted",
+                "requireApiKey": self.api_key.is_some(),
+                "version": ANKI_CONNECT_VERSION,
+            })),
+            "apiReflect" => self.api_reflect(params),
+            "getProfiles" => ok(["User 1"]),
+            "loadProfile" => ok(true),
+            "sync" | "reloadCollection" | "clearUnusedTags" => ok(()),
+            "multi" => self.multi(params),
+
+            // deck actions
+            "deckNames" => {
+                let mut names = self
+                    .decks
+                    .values()
+                    .map(|deck| deck.name.clone())
+                    .collect::<Vec<_>>();
+                names.sort();
+                ok(names)
+            }
+            "deckNamesAndIds" => ok(self
+                .decks
+                .values()
+                .map(|deck| (deck.name.clone(), deck.id))
+                .collect::<BTreeMap<_, _>>()),
+            "createDeck" => {
+                let name = param::<String>(params, "deck")?;
+                ok(self.create_deck(&name))
+            }
+            "deleteDecks" => self.delete_decks(params),
+            "changeDeck" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                let deck_id = self.create_deck(&param::<String>(params, "deck")?);
+                for card in self
+                    .cards
+                    .values_mut()
+                    .filter(|card| cards.contains(&card.id))
+                {
+                    card.deck_id = deck_id;
+                    card.modified = now();
+                }
+                ok(())
+            }
+            "getDecks" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                let mut decks = BTreeMap::<String, Vec<i64>>::new();
+                for card in cards.iter().filter_map(|id| self.cards.get(id)) {
+                    if let Some(deck) = self.decks.get(&card.deck_id) {
+                        decks.entry(deck.name.clone()).or_default().push(card.id);
+                    }
+                }
+                ok(decks)
+            }
+            "getDeckStats" => self.deck_stats(params),
+            "getDeckConfig" => {
+                let name = param::<String>(params, "deck")?;
+                match self.deck_by_name(&name) {
+                    Some(deck) => Ok(self.deck_configs[&deck.config_id].clone()),
+                    None => ok(false),
+                }
+            }
+            "saveDeckConfig" => {
+                let config = param::<Value>(params, "config")?;
+                let Some(id) = config.get("id").and_then(Value::as_i64) else {
+                    return ok(false);
+                };
+                match self.deck_configs.get_mut(&id) {
+                    Some(existing) => {
+                        *existing = config;
+                        existing["mod"] = json!(now());
+                        ok(true)
+                    }
+                    None => ok(false),
+                }
+            }
+            "setDeckConfigId" => {
+                let names = param::<Vec<String>>(params, "decks")?;
+                let config_id = param::<i64>(params, "configId")?;
+                let ids = names
+                    .iter()
+                    .map(|name| self.deck_by_name(name).map(|deck| deck.id))
+                    .collect::<Option<Vec<_>>>();
+                match ids {
+                    Some(ids) if self.deck_configs.contains_key(&config_id) => {
+                        for id in ids {
+                            if let Some(deck) = self.decks.get_mut(&id) {
+                                deck.config_id = config_id;
+                            }
+                        }
+                        ok(true)
+                    }
+                    _ => ok(false),
+                }
+            }
+            "cloneDeckConfigId" => {
+                let name = param::<String>(params, "name")?;
+                let clone_from = param::<Option<i64>>(params, "cloneFrom")?.unwrap_or(1);
+                let Some(mut config) = self.deck_configs.get(&clone_from).cloned() else {
+                    return ok(false);
+                };
+                let id = self.next_id();
+                config["id"] = json!(id);
+                config["name"] = json!(name);
+                config["mod"] = json!(now());
+                self.deck_configs.insert(id, config);
+                ok(id)
+            }
+            "removeDeckConfigId" => {
+                let config_id = param::<i64>(params, "configId")?;
+                if config_id == 1 || self.deck_configs.remove(&config_id).is_none() {
+                    return ok(false);
+                }
+                for deck in self
+                    .decks
+                    .values_mut()
+                    .filter(|deck| deck.config_id == config_id)
+                {
+                    deck.config_id = 1;
+                }
+                ok(true)
+            }
+
+            // model actions
+            "modelNames" => ok(self
+                .models
+                .values()
+                .map(|model| model.name.clone())
+                .collect::<Vec<_>>()),
+            "modelNamesAndIds" => ok(self
+                .models
+                .values()
+                .map(|model| (model.name.clone(), model.id))
+                .collect::<BTreeMap<_, _>>()),
+            "modelFieldNames" => ok(&self.model(params)?.fields),
+            "modelTemplates" => ok(self
+                .model(params)?
+                .templates
+                .iter()
+                .map(|template| {
+                    (
+                        template.name.clone(),
+                        json!({ "Front": template.front, "Back": template.back }),
+                    )
+                })
+                .collect::<BTreeMap<_, _>>()),
+            "modelStyling" => ok(json!({ "css": self.model(params)?.css })),
+            "createModel" => self.create_model(params),
+            "updateModelStyling" => {
+                let model = param::<Value>(params, "model")?;
+                let css = param::<String>(&model, "css")?;
+                self.model_mut(&model, "name")?.css = css;
+                ok(())
+            }
+            "updateModelTemplates" => {
+                let model = param::<Value>(params, "model")?;
+                let templates =
+                    param::<HashMap<String, HashMap<String, String>>>(&model, "templates")?;
+                let model = self.model_mut(&model, "name")?;
+                for (name, sides) in templates {
+                    let template = model
+                        .templates
+                        .iter_mut()
+                        .find(|template| template.name == name)
+                        .ok_or_else(|| format!("template was not found: {name}"))?;
+                    if let Some(front) = sides.get("Front") {
+                        template.front = front.clone();
+                    }
+                    if let Some(back) = sides.get("Back") {
+                        template.back = back.clone();
+                    }
+                }
+                ok(())
+            }
+            "modelFieldAdd" => self.model_field_add(params),
+            "modelFieldRemove" => self.model_field_remove(params),
+            "modelFieldRename" => {
+                let old = param::<String>(params, "oldFieldName")?;
+                let new = param::<String>(params, "newFieldName")?;
+                let model = self.model_mut(params, "modelName")?;
+                let field = model
+                    .fields
+                    .iter_mut()
+                    .find(|field| **field == old)
+                    .ok_or_else(|| format!("field was not found: {old}"))?;
+                *field = new;
+                ok(())
+            }
+            "modelFieldReposition" => self.model_field_reposition(params),
+            "modelTemplateAdd" => self.model_template_add(params),
+            "modelTemplateRemove" => self.model_template_remove(params),
+            "modelTemplateRename" => {
+                let old = param::<String>(params, "oldTemplateName")?;
+                let new = param::<String>(params, "newTemplateName")?;
+                let model = self.model_mut(params, "modelName")?;
+                let template = model
+                    .templates
+                    .iter_mut()
+                    .find(|template| template.name == old)
+                    .ok_or_else(|| format!("template was not found: {old}"))?;
+                template.name = new;
+                ok(())
+            }
+
+            // note actions
+            "addNote" => {
+                let note = param::<NewNote>(params, "note")?;
+                ok(self.add_note(&note)?)
+            }
+            "addNotes" => {
+                let notes = param::<Vec<NewNote>>(params, "notes")?;
+                ok(notes
+                    .iter()
+                    .map(|note| self.add_note(note).ok())
+                    .collect::<Vec<_>>())
+            }
+            "canAddNotes" => {
+                let notes = param::<Vec<NewNote>>(params, "notes")?;
+                ok(notes
+                    .iter()
+                    .map(|note| self.check_note(note).is_ok())
+                    .collect::<Vec<_>>())
+            }
+            "canAddNotesWithErrorDetail" => {
+                let notes = param::<Vec<NewNote>>(params, "notes")?;
+                ok(notes
+                    .iter()
+                    .map(|note| match self.check_note(note) {
+                        Ok(_) => json!({ "canAdd": true }),
+                        Err(error) => json!({ "canAdd": false, "error": error }),
+                    })
+                    .collect::<Vec<_>>())
+            }
+            "updateNoteFields" => {
+                let note = param::<Value>(params, "note")?;
+                let fields = param::<HashMap<String, String>>(&note, "fields")?;
+                self.update_note(param(&note, "id")?, Some(fields), None)
+            }
+            "updateNote" => {
+                let note = param::<Value>(params, "note")?;
+                self.update_note(
+                    param(&note, "id")?,
+                    param(&note, "fields")?,
+                    param(&note, "tags")?,
+                )
+            }
+            "updateNoteTags" => {
+                self.update_note(param(params, "note")?, None, Some(param(params, "tags")?))
+            }
+            "updateNoteModel" => self.update_note_model(params),
+            "getNoteTags" => ok(&self.note_mut(param(params, "note")?)?.tags),
+            "deleteNotes" => {
+                let notes = param::<BTreeSet<i64>>(params, "notes")?;
+                self.remove_notes(&notes);
+                ok(())
+            }
+            "findNotes" => ok(search::find_notes(
+                self,
+                &param::<String>(params, "query")?,
+            )?),
+            "notesInfo" => {
+                let notes = param::<Vec<i64>>(params, "notes")?;
+                ok(notes
+                    .iter()
+                    .map(|id| self.note_info(*id))
+                    .collect::<Vec<_>>())
+            }
+            "getTags" => ok(self
+                .notes
+                .values()
+                .flat_map(|note| note.tags.iter().cloned())
+                .collect::<BTreeSet<_>>()),
+            "addTags" | "removeTags" => {
+                let notes = param::<Vec<i64>>(params, "notes")?;
+                let tags = param::<String>(params, "tags")?;
+                let tags = tags.split_whitespace().collect::<Vec<_>>();
+                for note in self
+                    .notes
+                    .values_mut()
+                    .filter(|note| notes.contains(&note.id))
+                {
+                    if action == "addTags" {
+                        for tag in &tags {
+                            if !note.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                                note.tags.push((*tag).to_string());
+                            }
+                        }
+                    } else {
+                        note.tags
+                            .retain(|t| !tags.iter().any(|tag| t.eq_ignore_ascii_case(tag)));
+                    }
+                    note.modified = now();
+                }
+                ok(())
+            }
+            "replaceTags" | "replaceTagsInAllNotes" => {
+                let notes = param::<Option<Vec<i64>>>(params, "notes")?;
+                let old = param::<String>(params, "tag_to_replace")?;
+                let new = param::<String>(params, "replace_with_tag")?;
+                for note in self.notes.values_mut().filter(|note| {
+                    action == "replaceTagsInAllNotes"
+                        || notes.as_ref().is_some_and(|notes| notes.contains(&note.id))
+                }) {
+                    for tag in note
+                        .tags
+                        .iter_mut()
+                        .filter(|tag| tag.eq_ignore_ascii_case(&old))
+                    {
+                        tag.clone_from(&new);
+                    }
+                    note.modified = now();
+                }
+                ok(())
+            }
+
+            // card actions
+            "findCards" => ok(search::find_cards(
+                self,
+                &param::<String>(params, "query")?,
+            )?),
+            "cardsInfo" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                ok(cards
+                    .iter()
+                    .map(|id| self.card_info(*id))
+                    .collect::<Vec<_>>())
+            }
+            "cardsToNotes" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                let mut notes = Vec::new();
+                for card in cards.iter().filter_map(|id| self.cards.get(id)) {
+                    if !notes.contains(&card.note_id) {
+                        notes.push(card.note_id);
+                    }
+                }
+                ok(notes)
+            }
+            "cardsModTime" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                ok(cards
+                    .iter()
+                    .filter_map(|id| self.cards.get(id))
+                    .map(|card| json!({ "cardId": card.id, "mod": card.modified }))
+                    .collect::<Vec<_>>())
+            }
+            "suspend" | "unsuspend" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                let suspend = action != "unsuspend";
+                for card in self
+                    .cards
+                    .values_mut()
+                    .filter(|card| cards.contains(&card.id))
+                {
+                    card.queue = match (suspend, card.card_type) {
+                        (true, _) => -1,
+                        (false, 3) => 1,
+                        (false, card_type) => card_type,
+                    };
+                    card.modified = now();
+                }
+                ok(true)
+            }
+            "suspended" => {
+                let id = param::<i64>(params, "card")?;
+                let card = self
+                    .cards
+                    .get(&id)
+                    .ok_or_else(|| format!("card was not found: {id}"))?;
+                ok(card.queue == -1)
+            }
+            "areSuspended" => ok(self.map_cards(params, |_, card| json!(card.queue == -1))?),
+            "areDue" => {
+                ok(self.map_cards(params, |collection, card| json!(collection.is_due(card)))?)
+            }
+            "getIntervals" => ok(self.map_cards(params, |_, card| json!(card.interval))?),
+            "getEaseFactors" => ok(self.map_cards(params, |_, card| json!(card.factor))?),
+            "setEaseFactors" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                let factors = param::<Vec<i64>>(params, "easeFactors")?;
+                ok(cards
+                    .iter()
+                    .zip(factors)
+                    .map(|(id, factor)| match self.cards.get_mut(id) {
+                        Some(card) => {
+                            card.factor = factor;
+                            card.modified = now();
+                            true
+                        }
+                        None => false,
+                    })
+                    .collect::<Vec<_>>())
+            }
+            "setSpecificValueOfCard" => self.set_card_values(params),
+            "forgetCards" | "relearnCards" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                for card in self
+                    .cards
+                    .values_mut()
+                    .filter(|card| cards.contains(&card.id))
+                {
+                    if action == "forgetCards" {
+                        card.card_type = 0;
+                        card.queue = 0;
+                        card.interval = 0;
+                        card.reps = 0;
+                        card.lapses = 0;
+                    } else {
+                        card.card_type = 3;
+                        card.queue = 1;
+                    }
+                    card.modified = now();
+                }
+                ok(())
+            }
+
+            // statistic actions
+            "insertReviews" => self.insert_reviews(params),
+            "cardReviews" => {
+                let deck = param::<String>(params, "deck")?;
+                let start = param::<i64>(params, "startID")?;
+                let decks = self.deck_with_children(&deck);
+                ok(self
+                    .reviews
+                    .iter()
+                    .filter(|review| review.id > start)
+                    .filter(|review| {
+                        self.cards
+                            .get(&review.card_id)
+                            .is_some_and(|card| decks.contains(&card.deck_id))
+                    })
+                    .map(|review| {
+                        json!([
+                            review.id,
+                            review.card_id,
+                            review.usn,
+                            review.ease,
+                            review.interval,
+                            review.last_interval,
+                            review.factor,
+                            review.time,
+                            review.review_type,
+                        ])
+                    })
+                    .collect::<Vec<_>>())
+            }
+            "getReviewsOfCards" => {
+                let cards = param::<Vec<i64>>(params, "cards")?;
+                ok(cards
+                    .iter()
+                    .map(|id| {
+                        let reviews = self
+                            .reviews
+                            .iter()
+                            .filter(|review| review.card_id == *id)
+                            .map(|review| {
+                                json!({
+                                    "id": review.id,
+                                    "usn": review.usn,
+                                    "ease": review.ease,
+                                    "ivl": review.interval,
+                                    "lastIvl": review.last_interval,
+                                    "factor": review.factor,
+                                    "time": review.time,
+                                    "type": review.review_type,
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        (id.to_string(), reviews)
+                    })
+                    .collect::<BTreeMap<_, _>>())
+            }
+            "getLatestReviewID" => {
+                let decks = self.deck_with_children(&param::<String>(params, "deck")?);
+                ok(self
+                    .reviews
+                    .iter()
+                    .filter(|review| {
+                        self.cards
+                            .get(&review.card_id)
+                            .is_some_and(|card| decks.contains(&card.deck_id))
+                    })
+                    .map(|review| review.id)
+                    .max()
+                    .unwrap_or(0))
+            }
+            "getNumCardsReviewedToday" => {
+                let today = now() / SECONDS_PER_DAY;
+                ok(self
+                    .reviews
+                    .iter()
+                    .filter(|review| review.id / 1000 / SECONDS_PER_DAY == today)
+                    .count())
+            }
+            "getNumCardsReviewedByDay" => {
+                let mut days = BTreeMap::<i64, usize>::new();
+                for review in &self.reviews {
+                    *days.entry(review.id / 1000 / SECONDS_PER_DAY).or_default() += 1;
+                }
+                ok(days
+                    .into_iter()
+                    .rev()
+                    .map(|(day, count)| json!([date_string(day), count]))
+                    .collect::<Vec<_>>())
+            }
+
+            _ => Err("unsupported action".to_string()),
+        }
+    }
+
+    fn api_reflect(&self, params: &Value) -> ActionResult {
+        let scopes = param::<Vec<String>>(params, "scopes")?;
+        let actions = param::<Option<Vec<String>>>(params, "actions")?;
+        if !scopes.iter().any(|scope| scope == "actions") {
+            return ok(json!({ "scopes": [] }));
+        }
+        let actions = ACTIONS
+            .iter()
+            .filter(|action| {
+                actions
+                    .as_ref()
+                    .is_none_or(|actions| actions.iter().any(|a| a == *action))
+            })
+            .collect::<Vec<_>>();
+        ok(json!({ "scopes": ["actions"], "actions": actions }))
+    }
+
+    fn multi(&mut self, params: &Value) -> ActionResult {
+        let actions = param::<Vec<Value>>(params, "actions")?;
+        ok(actions
+            .iter()
+            .map(|action| {
+                let name = action.get("action").and_then(Value::as_str).unwrap_or("");
+                let params = action.get("params").cloned().unwrap_or(Value::Null);
+                match self.handle(name, &params) {
+                    Ok(result) => json!({ "result": result, "error": null }),
+                    Err(error) => json!({ "result": null, "error": error }),
+                }
+            })
+            .collect::<Vec<_>>())
+    }
+
+    fn delete_decks(&mut self, params: &Value) -> ActionResult {
+        let names = param::<Vec<String>>(params, "decks")?;
+        if !param::<Option<bool>>(params, "cardsToo")?.unwrap_or(false) {
+            return Err(
+                "Since Anki 2.1.28 it's not possible to delete decks without deleting cards as well"
+                    .to_string(),
+            );
+        }
+        let decks = names
+            .iter()
+            .flat_map(|name| self.deck_with_children(name))
+            .collect::<BTreeSet<_>>();
+        if decks.contains(&DEFAULT_DECK_ID) {
+            return Err("the default deck can't be deleted".to_string());
+        }
+        let cards = self
+            .cards
+            .values()
+            .filter(|card| decks.contains(&card.deck_id))
+            .map(|card| card.id)
+            .collect();
+        self.remove_cards(&cards);
+        self.decks.retain(|id, _| !decks.contains(id));
+        ok(())
+    }
+
+    fn deck_stats(&self, params: &Value) -> ActionResult {
+        let names = param::<Vec<String>>(params, "decks")?;
+        let mut stats = Map::new();
+        for deck in names.iter().filter_map(|name| self.deck_by_name(name)) {
+            // Like Anki, the due counts include the subdecks, but the total doesn't.
+            let prefix = format!("{}::", deck.name);
+            let cards = self
+                .cards
+                .values()
+                .filter(|card| {
+                    self.decks.get(&card.deck_id).is_some_and(|parent| {
+                        parent.id == deck.id || parent.name.starts_with(&prefix)
+                    })
+                })
+                .collect::<Vec<_>>();
+            let count =
+                |filter: &dyn Fn(&Card) -> bool| cards.iter().filter(|card| filter(card)).count();
+            stats.insert(
+                deck.id.to_string(),
+                json!({
+                    "deck_id": deck.id,
+                    "name": deck.name,
+                    "new_count": count(&|card| card.queue == 0),
+                    "learn_count": count(&|card| matches!(card.queue, 1 | 3)),
+                    "review_count": count(&|card| card.queue == 2 && card.due <= self.today),
+                    "total_in_deck": count(&|card| card.deck_id == deck.id),
+                }),
+            );
+        }
+        Ok(Value::Object(stats))
+    }
+
+    fn model(&self, params: &Value) -> std::result::Result<&Model, String> {
+        let name = param::<String>(params, "modelName")?;
+        self.model_by_name(&name)
+            .ok_or_else(|| format!("model was not found: {name}"))
+    }
+
+    fn model_mut(&mut self, params: &Value, key: &str) -> std::result::Result<&mut Model, String> {
+        let name = param::<String>(params, key)?;
+        self.models
+            .values_mut()
+            .find(|model| model.name == name)
+            .ok_or_else(|| format!("model was not found: {name}"))
+    }
+
+    fn create_model(&mut self, params: &Value) -> ActionResult {
+        let name = param::<String>(params, "modelName")?;
+        let fields = param::<Vec<String>>(params, "inOrderFields")?;
+        let css = param::<Option<String>>(params, "css")?;
+        let is_cloze = param::<Option<bool>>(params, "isCloze")?.unwrap_or(false);
+        let templates = param::<Vec<HashMap<String, String>>>(params, "cardTemplates")?;
+        if self.model_by_name(&name).is_some() {
+            return Err("Model name already exists".to_string());
+        }
+        if fields.is_empty() {
+            return Err("Must provide at least one field for inOrderFields".to_string());
+        }
+        if templates.is_empty() {
+            return Err("Must provide at least one card for cardTemplates".to_string());
+        }
+        let templates = templates
+            .into_iter()
+            .enumerate()
+            .map(|(index, template)| Template {
+                name: template
+                    .get("Name")
+                    .cloned()
+                    .unwrap_or_else(|| format!("Card {}", index + 1)),
+                front: template.get("Front").cloned().unwrap_or_default(),
+                back: template.get("Back").cloned().unwrap_or_default(),
+            })
+            .collect();
+        let fields = fields.iter().map(String::as_str).collect::<Vec<_>>();
+        let id = self.add_model(&name, &fields, templates, is_cloze);
+        let model = self.models.get_mut(&id).expect("the model was just added");
+        if let Some(css) = css {
+            model.css = css;
+        }
+        ok(json!({
+            "id": model.id,
+            "name": model.name,
+            "type": i32::from(model.is_cloze),
+            "css": model.css,
+            "sortf": 0,
+            "did": 1,
+            "flds": model.fields.iter().enumerate().map(|(ord, name)| json!({ "name": name, "ord": ord })).collect::<Vec<_>>(),
+            "tmpls": model.templates.iter().enumerate().map(|(ord, template)| json!({
+                "name": template.name,
+                "ord": ord,
+                "qfmt": template.front,
+                "afmt": template.back,
+            })).collect::<Vec<_>>(),
+        }))
+    }
+
+    fn model_field_add(&mut self, params: &Value) -> ActionResult {
+        let field = param::<String>(params, "fieldName")?;
+        let index = param::<Option<usize>>(params, "index")?;
+        let model = self.model_mut(params, "modelName")?;
+        if model.fields.contains(&field) {
+            return Err(format!("field already exists: {field}"));
+        }
+        let index = index.unwrap_or(model.fields.len()).min(model.fields.len());
+        model.fields.insert(index, field);
+        let model_id = model.id;
+        for note in self
+            .notes
+            .values_mut()
+            .filter(|note| note.model_id == model_id)
+        {
+            note.fields.insert(index, String::new());
+        }
+        ok(())
+    }
+
+    fn model_field_remove(&mut self, params: &Value) -> ActionResult {
+        let field = param::<String>(params, "fieldName")?;
+        let model = self.model_mut(params, "modelName")?;
+        let index = model
+            .fields
+            .iter()
+            .position(|name| *name == field)
+            .ok_or_else(|| format!("field was not found: {field}"))?;
+        model.fields.remove(index);
+        let model_id = model.id;
+        for note in self
+            .notes
+            .values_mut()
+            .filter(|note| note.model_id == model_id)
+        {
+            note.fields.remove(index);
+        }
+        ok(())
+    }
+
+    fn model_field_reposition(&mut self, params: &Value) -> ActionResult {
+        let field = param::<String>(params, "fieldName")?;
+        let target = param::<usize>(params, "index")?;
+        let model = self.model_mut(params, "modelName")?;
+        let index = model
+            .fields
+            .iter()
+            .position(|name| *name == field)
+            .ok_or_else(|| format!("field was not found: {field}"))?;
+        let target = target.min(model.fields.len() - 1);
+        let name = model.fields.remove(index);
+        model.fields.insert(target, name);
+        let model_id = model.id;
+        for note in self
+            .notes
+            .values_mut()
+            .filter(|note| note.model_id == model_id)
+        {
+            let value = note.fields.remove(index);
+            note.fields.insert(target, value);
+        }
+        ok(())
+    }
+
+    fn model_template_add(&mut self, params: &Value) -> ActionResult {
+        let template = param::<HashMap<String, String>>(params, "template")?;
+        let model = self.model_mut(params, "modelName")?;
+        let name = template
+            .get("Name")
+            .cloned()
+            .unwrap_or_else(|| format!("Card {}", model.templates.len() + 1));
+        if model.templates.iter().any(|template| template.name == name) {
+            return Err(format!("template already exists: {name}"));
+        }
+        model.templates.push(Template {
+            name,
+            front: template.get("Front").cloned().unwrap_or_default(),
+            back: template.get("Back").cloned().unwrap_or_default(),
+        });
+        let model_id = model.id;
+        let notes = self
+            .notes
+            .values()
+            .filter(|note| note.model_id == model_id)
+            .filter_map(|note| Some((note.id, self.cards_of_note(note.id).next()?.deck_id)))
+            .collect::<Vec<_>>();
+        for (note_id, deck_id) in notes {
+            self.generate_cards(note_id, deck_id);
+        }
+        ok(())
+    }
+
+    fn model_template_remove(&mut self, params: &Value) -> ActionResult {
+        let name = param::<String>(params, "templateName")?;
+        let model = self.model_mut(params, "modelName")?;
+        let ord = model
+            .templates
+            .iter()
+            .position(|template| template.name == name)
+            .ok_or_else(|| format!("template was not found: {name}"))?;
+        if model.templates.len() == 1 {
+            return Err("cannot remove the last template".to_string());
+        }
+        model.templates.remove(ord);
+        let model_id = model.id;
+        let notes = self
+            .notes
+            .values()
+            .filter(|note| note.model_id == model_id)
+            .map(|note| note.id)
+            .collect::<BTreeSet<_>>();
+        let removed = self
+            .cards
+            .values()
+            .filter(|card| notes.contains(&card.note_id) && card.ord == ord)
+            .map(|card| card.id)
+            .collect();
+        self.remove_cards(&removed);
+        for card in self
+            .cards
+            .values_mut()
+            .filter(|card| notes.contains(&card.note_id) && card.ord > ord)
+        {
+            card.ord -= 1;
+        }
+        ok(())
+    }
+
+    /// Validates the note and returns its model id, deck id and fields in the order of the model.
+    fn check_note(&self, note: &NewNote) -> std::result::Result<(i64, i64, Vec<String>), String> {
+        let model = self
+            .model_by_name(&note.model_name)
+            .ok_or_else(|| format!("model was not found: {}", note.model_name))?;
+        let deck = self
+            .deck_by_name(&note.deck_name)
+            .ok_or_else(|| format!("deck was not found: {}", note.deck_name))?;
+        let fields = model
+            .fields
+            .iter()
+            .map(|name| note.fields.get(name).cloned().unwrap_or_default())
+            .collect::<Vec<_>>();
+        if fields.first().is_none_or(|field| field.trim().is_empty()) {
+            return Err("cannot create note because it is empty".to_string());
+        }
+        if model.is_cloze
+            && !fields
+                .iter()
+                .any(|field| cloze_numbers(field).next().is_some())
+        {
+            return Err("cannot create note because it has no cloze deletions".to_string());
+        }
+        if !note.options.allow_duplicate && self.is_duplicate(note, model.id, &fields[0]) {
+            return Err("cannot create note because it is a duplicate".to_string());
+        }
+        Ok((model.id, deck.id, fields))
+    }
+
+    fn is_duplicate(&self, note: &NewNote, model_id: i64, first_field: &str) -> bool {
+        let options = &note.options.duplicate_scope_options;
+        let decks = (note.options.duplicate_scope.as_deref() == Some("deck")).then(|| {
+            let name = options.deck_name.as_deref().unwrap_or(&note.deck_name);
+            if options.check_children {
+                self.deck_with_children(name)
+            } else {
+                self.deck_by_name(name)
+                    .map(|deck| deck.id)
+                    .into_iter()
+                    .collect()
+            }
+        });
+        self.notes.values().any(|existing| {
+            (options.check_all_models || existing.model_id == model_id)
+                && existing.fields.first().map(|field| field.trim()) == Some(first_field.trim())
+                && decks.as_ref().is_none_or(|decks| {
+                    self.cards_of_note(existing.id)
+                        .any(|card| decks.contains(&card.deck_id))
+                })
+        })
+    }
+
+    fn add_note(&mut self, note: &NewNote) -> std::result::Result<i64, String> {
+        let (model_id, deck_id, fields) = self.check_note(note)?;
+        let id = self.next_id();
+        let mut tags = Vec::<String>::new();
+        for tag in note.tags.iter().flat_map(|tag| tag.split_whitespace()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        self.notes.insert(
+            id,
+            Note {
+                id,
+                model_id,
+                fields,
+                tags,
+                modified: now(),
+            },
+        );
+        self.generate_cards(id, deck_id);
+        Ok(id)
+    }
+
+    fn note_mut(&mut self, id: i64) -> std::result::Result<&mut Note, String> {
+        self.notes
+            .get_mut(&id)
+            .ok_or_else(|| format!("note was not found: {id}"))
+    }
+
+    fn update_note(
+        &mut self,
+        id: i64,
+        fields: Option<HashMap<String, String>>,
+        tags: Option<Vec<String>>,
+    ) -> ActionResult {
+        let model_id = self.note_mut(id)?.model_id;
+        let names = self.models[&model_id].fields.clone();
+        let note = self.note_mut(id)?;
+        for (name, value) in fields.unwrap_or_default() {
+            if let Some(index) = names.iter().position(|field| *field == name) {
+                note.fields[index] = value;
+            }
+        }
+        if let Some(tags) = tags {
+            note.tags = tags;
+        }
+        note.modified = now();
+        let deck_id = self.cards_of_note(id).next().map_or(1, |card| card.deck_id);
+        self.generate_cards(id, deck_id);
+        ok(())
+    }
+
+    fn update_note_model(&mut self, params: &Value) -> ActionResult {
+        let note = param::<Value>(params, "note")?;
+        let id = param::<i64>(&note, "id")?;
+        let fields = param::<HashMap<String, String>>(&note, "fields")?;
+        let tags = param::<Option<Vec<String>>>(&note, "tags")?;
+        let model = self.model(&note)?;
+        let (model_id, templates) = (model.id, model.templates.len());
+        let values = model
+            .fields
+            .iter()
+            .map(|name| fields.get(name).cloned().unwrap_or_default())
+            .collect();
+        let existing = self.note_mut(id)?;
+        existing.model_id = model_id;
+        existing.fields = values;
+        if let Some(tags) = tags {
+            existing.tags = tags;
+        }
+        existing.modified = now();
+        let removed = self
+            .cards_of_note(id)
+            .filter(|card| card.ord >= templates)
+            .map(|card| card.id)
+            .collect();
+        let deck_id = self.cards_of_note(id).next().map_or(1, |card| card.deck_id);
+        self.remove_cards(&removed);
+        if self.notes.contains_key(&id) {
+            self.generate_cards(id, deck_id);
+        }
+        ok(())
+    }
+
+    fn note_info(&self, id: i64) -> Value {
+        let Some(note) = self.notes.get(&id) else {
+            return json!({});
+        };
+        let model = &self.models[&note.model_id];
+        json!({
+            "noteId": note.id,
+            "profile": "User 1",
+            "modelName": model.name,
+            "tags": note.tags,
+            "fields": fields_json(&model.fields, &note.fields),
+            "mod": note.modified,
+            "cards": self.cards_of_note(id).map(|card| card.id).collect::<Vec<_>>(),
+        })
+    }
+
+    fn card_info(&self, id: i64) -> Value {
+        let Some(card) = self.cards.get(&id) else {
+            return json!({});
+        };
+        let note = &self.notes[&card.note_id];
+        let model = &self.models[&note.model_id];
+        let template = &model.templates[if model.is_cloze { 0 } else { card.ord }];
+        let question = render(&template.front, &model.fields, &note.fields, "");
+        let answer = render(&template.back, &model.fields, &note.fields, &question);
+        json!({
+            "answer": answer,
+            "question": question,
+            "deckName": self.decks.get(&card.deck_id).map(|deck| deck.name.clone()),
+            "modelName": model.name,
+            "fieldOrder": card.ord,
+            "fields": fields_json(&model.fields, &note.fields),
+            "css": model.css,
+            "cardId": card.id,
+            "interval": card.interval,
+            "note": card.note_id,
+            "ord": card.ord,
+            "type": card.card_type,
+            "queue": card.queue,
+            "due": card.due,
+            "reps": card.reps,
+            "lapses": card.lapses,
+            "left": card.left,
+            "mod": card.modified,
+            "factor": card.factor,
+        })
+    }
+
+    fn map_cards(
+        &self,
+        params: &Value,
+        map: impl Fn(&Self, &Card) -> Value,
+    ) -> std::result::Result<Vec<Value>, String> {
+        let cards = param::<Vec<i64>>(params, "cards")?;
+        Ok(cards
+            .iter()
+            .map(|id| {
+                self.cards
+                    .get(id)
+                    .map_or(Value::Null, |card| map(self, card))
+            })
+            .collect())
+    }
+
+    fn set_card_values(&mut self, params: &Value) -> ActionResult {
+        let id = param::<i64>(params, "card")?;
+        let keys = param::<Vec<String>>(params, "keys")?;
+        let values = param::<Vec<Value>>(params, "newValues")?;
+        let card = self
+            .cards
+            .get_mut(&id)
+            .ok_or_else(|| format!("card was not found: {id}"))?;
+        let results = keys
+            .iter()
+            .zip(values)
+            .map(|(key, value)| {
+                let value = value
+                    .as_i64()
+                    .or_else(|| value.as_str().and_then(|value| value.parse().ok()));
+                let field = match key.as_str() {
+                    "type" => &mut card.card_type,
+                    "queue" => &mut card.queue,
+                    "due" => &mut card.due,
+                    "ivl" => &mut card.interval,
+                    "factor" => &mut card.factor,
+                    "reps" => &mut card.reps,
+                    "lapses" => &mut card.lapses,
+                    "left" => &mut card.left,
+                    "mod" => &mut card.modified,
+                    _ => return false,
+                };
+                value.map(|value| *field = value).is_some()
+            })
+            .collect::<Vec<_>>();
+        ok(results)
+    }
+
+    fn insert_reviews(&mut self, params: &Value) -> ActionResult {
+        let reviews = param::<Vec<[i64; 9]>>(params, "reviews")?;
+        for [id, card_id, usn, ease, interval, last_interval, factor, time, review_type] in reviews
+        {
+            if self.reviews.iter().any(|review| review.id == id) {
+                continue;
+            }
+            self.reviews.push(Review {
+                id,
+                card_id,
+                usn,
+                ease,
+                interval,
+                last_interval,
+                factor,
+                time,
+                review_type,
+            });
+        }
+        self.reviews.sort_by_key(|review| review.id);
+        ok(())
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+fn fields_json(names: &[String], values: &[String]) -> Value {
+    Value::Object(
+        names
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(order, (name, value))| (name.clone(), json!({ "value": value, "order": order })))
+            .collect(),
+    )
+}
+
+/// Replaces the field references of the template, but ignores filters and conditionals.
+fn render(template: &str, names: &[String], values: &[String], front_side: &str) -> String {
+    let mut rendered = template.replace("{{FrontSide}}", front_side);
+    for (name, value) in names.iter().zip(values) {
+        rendered = rendered
+            .replace(&format!("{{{{{name}}}}}"), value)
+            .replace(&format!("{{{{cloze:{name}}}}}"), value);
+    }
+    rendered
+}
+
+/// Formats the days since the unix epoch as `YYYY-MM-DD`.
+fn date_string(days: i64) -> String {
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_string() {
+        assert_eq!(date_string(0), "1970-01-01");
+        assert_eq!(date_string(19_723), "2024-01-01");
+        assert_eq!(date_string(19_782), "2024-02-29");
+    }
+}
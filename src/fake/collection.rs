@@ -0,0 +1,410 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Value};
+
+/// The separator between the parent and child names of a deck.
+pub(crate) const DECK_SEPARATOR: &str = "::";
+
+/// The separator between the number and the answer of a cloze deletion, e.g. `{{c1::answer}}`.
+const CLOZE_SEPARATOR: &str = "::";
+
+/// The id of the "Default" deck, which can't be deleted.
+pub(crate) const DEFAULT_DECK_ID: i64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Deck {
+    pub id: i64,
+    pub name: String,
+    pub config_id: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Template {
+    pub name: String,
+    pub front: String,
+    pub back: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Model {
+    pub id: i64,
+    pub name: String,
+    pub fields: Vec<String>,
+    pub templates: Vec<Template>,
+    pub css: String,
+    pub is_cloze: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Note {
+    pub id: i64,
+    pub model_id: i64,
+    /// The values of the fields in the order of [`Model::fields`].
+    pub fields: Vec<String>,
+    pub tags: Vec<String>,
+    pub modified: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Card {
+    pub id: i64,
+    pub note_id: i64,
+    pub deck_id: i64,
+    pub ord: usize,
+    pub card_type: i64,
+    pub queue: i64,
+    pub due: i64,
+    pub interval: i64,
+    pub factor: i64,
+    pub reps: i64,
+    pub lapses: i64,
+    pub left: i64,
+    pub modified: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Review {
+    pub id: i64,
+    pub card_id: i64,
+    pub usn: i64,
+    pub ease: i64,
+    pub interval: i64,
+    pub last_interval: i64,
+    pub factor: i64,
+    pub time: i64,
+    pub review_type: i64,
+}
+
+/// The state of the fake collection, which is shared by all clones of a
+/// [`FakeAnki`](super::FakeAnki).
+#[derive(Debug, Clone)]
+pub(crate) struct Collection {
+    pub decks: BTreeMap<i64, Deck>,
+    pub deck_configs: BTreeMap<i64, Value>,
+    pub models: BTreeMap<i64, Model>,
+    pub notes: BTreeMap<i64, Note>,
+    pub cards: BTreeMap<i64, Card>,
+    pub reviews: Vec<Review>,
+    /// The day number, which is compared against the due date of review cards.
+    pub today: i64,
+    /// The API key, which has to be sent with every request over HTTP.
+    pub api_key: Option<String>,
+    next_id: i64,
+}
+
+impl Default for Collection {
+    /// Creates a collection like a fresh Anki profile, with the "Default" deck and the "Basic",
+    /// "Basic (and reversed card)" and "Cloze" note types.
+    fn default() -> Self {
+        let mut collection = Self {
+            decks: BTreeMap::new(),
+            deck_configs: BTreeMap::from([(1, default_deck_config(1, "Default"))]),
+            models: BTreeMap::new(),
+            notes: BTreeMap::new(),
+            cards: BTreeMap::new(),
+            reviews: Vec::new(),
+            today: 0,
+            api_key: None,
+            next_id: 0,
+        };
+        collection.decks.insert(
+            DEFAULT_DECK_ID,
+            Deck {
+                id: DEFAULT_DECK_ID,
+                name: "Default".to_string(),
+                config_id: 1,
+            },
+        );
+        let basic = Template {
+            name: "Card 1".to_string(),
+            front: "{{Front}}".to_string(),
+            back: "{{FrontSide}}<hr id=answer>{{Back}}".to_string(),
+        };
+        let reversed = Template {
+            name: "Card 2".to_string(),
+            front: "{{Back}}".to_string(),
+            back: "{{FrontSide}}<hr id=answer>{{Front}}".to_string(),
+        };
+        let cloze = Template {
+            name: "Cloze".to_string(),
+            front: "{{cloze:Text}}".to_string(),
+            back: "{{cloze:Text}}<br>{{Back Extra}}".to_string(),
+        };
+        collection.add_model("Basic", &["Front", "Back"], vec![basic.clone()], false);
+        collection.add_model(
+            "Basic (and reversed card)",
+            &["Front", "Back"],
+            vec![basic, reversed],
+            false,
+        );
+        collection.add_model("Cloze", &["Text", "Back Extra"], vec![cloze], true);
+        collection
+    }
+}
+
+impl Collection {
+    /// Returns a new unique id, which is used for all objects of the collection. Like in Anki, it
+    /// is the creation time in milliseconds.
+    pub fn next_id(&mut self) -> i64 {
+        self.next_id = (self.next_id + 1).max(now() * 1000);
+        self.next_id
+    }
+
+    pub fn add_model(
+        &mut self,
+        name: &str,
+        fields: &[&str],
+        templates: Vec<Template>,
+        is_cloze: bool,
+    ) -> i64 {
+        let id = self.next_id();
+        self.models.insert(
+            id,
+            Model {
+                id,
+                name: name.to_string(),
+                fields: fields.iter().map(ToString::to_string).collect(),
+                templates,
+                css: DEFAULT_CSS.to_string(),
+                is_cloze,
+            },
+        );
+        id
+    }
+
+    pub fn deck_by_name(&self, name: &str) -> Option<&Deck> {
+        self.decks
+            .values()
+            .find(|deck| deck.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn model_by_name(&self, name: &str) -> Option<&Model> {
+        self.models.values().find(|model| model.name == name)
+    }
+
+    /// Creates the deck and all of its missing parents and returns the id of the deck.
+    pub fn create_deck(&mut self, name: &str) -> i64 {
+        let mut id = 0;
+        let mut path = String::new();
+        for part in name.split(DECK_SEPARATOR) {
+            if !path.is_empty() {
+                path.push_str(DECK_SEPARATOR);
+            }
+            path.push_str(part.trim());
+            id = match self.deck_by_name(&path) {
+                Some(deck) => deck.id,
+                None => {
+                    let id = self.next_id();
+                    self.decks.insert(
+                        id,
+                        Deck {
+                            id,
+                            name: path.clone(),
+                            config_id: 1,
+                        },
+                    );
+                    id
+                }
+            };
+        }
+        id
+    }
+
+    /// Returns the ids of the deck and all of its children.
+    pub fn deck_with_children(&self, name: &str) -> BTreeSet<i64> {
+        let prefix = format!("{}{DECK_SEPARATOR}", name.to_lowercase());
+        self.decks
+            .values()
+            .filter(|deck| {
+                let deck_name = deck.name.to_lowercase();
+                deck_name == name.to_lowercase() || deck_name.starts_with(&prefix)
+            })
+            .map(|deck| deck.id)
+            .collect()
+    }
+
+    pub fn cards_of_note(&self, note_id: i64) -> impl Iterator<Item = &Card> {
+        self.cards
+            .values()
+            .filter(move |card| card.note_id == note_id)
+    }
+
+    /// Returns the ordinals of the cards, which the note generates.
+    pub fn card_ordinals(model: &Model, fields: &[String]) -> Vec<usize> {
+        if model.is_cloze {
+            let ordinals = fields
+                .iter()
+                .flat_map(|field| cloze_numbers(field))
+                .map(|number| number.saturating_sub(1))
+                .collect::<BTreeSet<_>>();
+            if ordinals.is_empty() {
+                vec![0]
+            } else {
+                ordinals.into_iter().collect()
+            }
+        } else {
+            (0..model.templates.len()).collect()
+        }
+    }
+
+    /// Creates the missing cards of the note in the given deck.
+    pub fn generate_cards(&mut self, note_id: i64, deck_id: i64) {
+        let Some(note) = self.notes.get(&note_id) else {
+            return;
+        };
+        let Some(model) = self.models.get(&note.model_id) else {
+            return;
+        };
+        let existing = self
+            .cards_of_note(note_id)
+            .map(|card| card.ord)
+            .collect::<BTreeSet<_>>();
+        let missing = Self::card_ordinals(model, &note.fields)
+            .into_iter()
+            .filter(|ord| !existing.contains(ord))
+            .collect::<Vec<_>>();
+        for ord in missing {
+            let id = self.next_id();
+            let due = self.cards.len() as i64 + 1;
+            self.cards.insert(
+                id,
+                Card {
+                    id,
+                    note_id,
+                    deck_id,
+                    ord,
+                    card_type: 0,
+                    queue: 0,
+                    due,
+                    interval: 0,
+                    factor: 0,
+                    reps: 0,
+                    lapses: 0,
+                    left: 0,
+                    modified: now(),
+                },
+            );
+        }
+    }
+
+    /// Removes the notes and all of their cards.
+    pub fn remove_notes(&mut self, note_ids: &BTreeSet<i64>) {
+        self.notes.retain(|id, _| !note_ids.contains(id));
+        self.cards
+            .retain(|_, card| !note_ids.contains(&card.note_id));
+    }
+
+    /// Removes the cards and the notes, which have no cards left.
+    pub fn remove_cards(&mut self, card_ids: &BTreeSet<i64>) {
+        self.cards.retain(|id, _| !card_ids.contains(id));
+        let remaining = self
+            .cards
+            .values()
+            .map(|card| card.note_id)
+            .collect::<BTreeSet<_>>();
+        self.notes.retain(|id, _| remaining.contains(id));
+    }
+
+    /// Returns [true] if the card is in the learning queue or a review card, which is due today.
+    pub fn is_due(&self, card: &Card) -> bool {
+        matches!(card.queue, 1 | 3) || (card.queue == 2 && card.due <= self.today)
+    }
+}
+
+/// Returns the numbers of the cloze deletions, e.g. `2` for `{{c2::answer}}`.
+pub(crate) fn cloze_numbers(text: &str) -> impl Iterator<Item = usize> + '_ {
+    text.match_indices("{{c").filter_map(|(index, _)| {
+        let rest = &text[index + 3..];
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        rest[digits..]
+            .starts_with(CLOZE_SEPARATOR)
+            .then(|| rest[..digits].parse().ok())
+            .flatten()
+    })
+}
+
+/// Returns the current time in seconds since the unix epoch.
+pub(crate) fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+pub(crate) fn default_deck_config(id: i64, name: &str) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "mod": now(),
+        "usn": -1,
+        "dyn": false,
+        "autoplay": true,
+        "replayq": true,
+        "timer": 0,
+        "maxTaken": 60,
+        "buryInterdayLearning": false,
+        "newGatherPriority": 0,
+        "newMix": 0,
+        "newPerDayMinimum": 0,
+        "newSortOrder": 0,
+        "reviewOrder": 0,
+        "new": {
+            "bury": false,
+            "delays": [1.0, 10.0],
+            "initialFactor": 2500,
+            "ints": [1, 4, 0],
+            "order": 1,
+            "perDay": 20,
+        },
+        "lapse": {
+            "delays": [10.0],
+            "leechAction": 1,
+            "leechFails": 8,
+            "minInt": 1,
+            "mult": 0.0,
+        },
+        "rev": {
+            "bury": false,
+            "ease4": 1.3,
+            "hardFactor": 1.2,
+            "ivlFct": 1.0,
+            "maxIvl": 36500,
+            "perDay": 200,
+        },
+    })
+}
+
+const DEFAULT_CSS: &str = ".card {
+    font-family: arial;
+    font-size: 20px;
+    text-align: center;
+    color: black;
+    background-color: white;
+}
+";
@@ -0,0 +1,332 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+//! An in-memory fake of Anki, which emulates the semantics of `AnkiConnect`, so whole workflows
+//! can be tested without a running Anki.
+//!
+//! ```
+//! use anki_bridge::fake::FakeAnki;
+//! use anki_bridge::prelude::*;
+//!
+//! let anki = FakeAnki::new();
+//! anki.request(CreateDeckRequest {
+//!     deck: "Japanese::Vocabulary".to_string(),
+//! })
+//! .unwrap();
+//! let decks = anki.request(DeckNamesRequest {}).unwrap();
+//! assert_eq!(decks, vec!["Default", "Japanese", "Japanese::Vocabulary"]);
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde_json::{json, Value};
+
+use crate::{error::AnkiError, AnkiRequest, AnkiRequestable, AsyncAnkiRequestable, Error, Result};
+
+mod actions;
+mod collection;
+mod search;
+/// Module containing the HTTP server, which serves a [`FakeAnki`] like `AnkiConnect` does.
+#[cfg(feature = "fake_server")]
+pub mod server;
+
+use collection::Collection;
+
+/// A fake Anki with an in-memory collection of decks, deck configs, note types, notes, cards and
+/// reviews. It starts like a fresh profile with the "Default" deck and the built-in "Basic",
+/// "Basic (and reversed card)" and "Cloze" note types.
+///
+/// Clones share the collection, so a clone can be moved into the code under test while the
+/// original is used for the assertions.
+#[derive(Debug, Clone, Default)]
+pub struct FakeAnki {
+    collection: Arc<Mutex<Collection>>,
+}
+
+impl FakeAnki {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn collection(&self) -> MutexGuard<'_, Collection> {
+        self.collection
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Returns the names of all actions, which the fake supports.
+    #[must_use]
+    pub fn actions() -> &'static [&'static str] {
+        actions::ACTIONS
+    }
+
+    /// Executes the action with the given parameters and returns the result or the error
+    /// message, as `AnkiConnect` would put them into the response.
+    pub fn handle(&self, action: &str, params: &Value) -> std::result::Result<Value, String> {
+        self.collection().handle(action, params)
+    }
+
+    /// Requires the API key in every envelope, which is handled by
+    /// [`handle_envelope`](Self::handle_envelope).
+    pub fn set_api_key(&self, key: Option<String>) {
+        self.collection().api_key = key;
+    }
+
+    /// Handles a request envelope like `{"action": ..., "version": ..., "params": ..., "key": ...}`
+    /// and returns the response envelope, which `AnkiConnect` would send back.
+    #[must_use]
+    pub fn handle_envelope(&self, envelope: &Value) -> Value {
+        let version = envelope.get("version").and_then(Value::as_u64).unwrap_or(4);
+        let result = self.handle_checked(envelope);
+        match result {
+            Ok(result) if version <= 4 => result,
+            Ok(result) => json!({ "result": result, "error": null }),
+            Err(error) => json!({ "result": null, "error": error }),
+        }
+    }
+
+    fn handle_checked(&self, envelope: &Value) -> std::result::Result<Value, String> {
+        let action = envelope
+            .get("action")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "missing action".to_string())?;
+        let params = envelope.get("params").cloned().unwrap_or(Value::Null);
+        let mut collection = self.collection();
+        if let Some(expected) = &collection.api_key {
+            let key = envelope.get("key").and_then(Value::as_str);
+            if action != "requestPermission" && key != Some(expected.as_str()) {
+                return Err("valid api key must be provided".to_string());
+            }
+        }
+        collection.handle(action, &params)
+    }
+
+    /// Sets the day number, which decides whether review cards are due. It starts at `0`.
+    pub fn set_today(&self, today: i64) {
+        self.collection().today = today;
+    }
+
+    fn handle_request<Request: AnkiRequest>(&self, params: &Request) -> Result<Request::Response> {
+        let params = serde_json::to_value(params).map_err(|source| Error::Serialize {
+            action: Request::ACTION,
+            source,
+        })?;
+        let result = self
+            .handle(Request::ACTION, &params)
+            .map_err(|message| AnkiError::new(Request::ACTION, message))?;
+        serde_json::from_value(result.clone())
+            .map_err(|error| Error::serde(Request::ACTION, &result.to_string(), error))
+    }
+}
+
+impl<Request: AnkiRequest> AnkiRequestable<Request> for FakeAnki {
+    fn request(&self, params: Request) -> Result<Request::Response> {
+        self.handle_request(&params)
+    }
+}
+
+/// The asynchronous variant of the [`FakeAnki`], which shares the collection with the fake it was
+/// created from.
+#[derive(Debug, Clone, Default)]
+pub struct AsyncFakeAnki(pub FakeAnki);
+
+impl From<FakeAnki> for AsyncFakeAnki {
+    fn from(fake: FakeAnki) -> Self {
+        Self(fake)
+    }
+}
+
+#[async_trait::async_trait]
+impl<Request> AsyncAnkiRequestable<Request> for AsyncFakeAnki
+where
+    Request: AnkiRequest + Send + Sync + 'static,
+{
+    async fn request(&self, params: Request) -> Result<Request::Response> {
+        self.0.handle_request(&params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::AnkiErrorKind, model_actions::model_definition::ModelDefinition, prelude::*,
+    };
+
+    use super::*;
+
+    fn basic_note(deck: &str, front: &str) -> AddNoteNote {
+        AddNoteNote {
+            deck_name: deck.to_string(),
+            model_name: "Basic".to_string(),
+            fields: NoteFields::from([("Front", front), ("Back", "back")]),
+            tags: vec!["fake".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_note_workflow() {
+        let anki = FakeAnki::new();
+        anki.request(CreateDeckRequest {
+            deck: "Japanese".to_string(),
+        })
+        .unwrap();
+        let note = anki
+            .request(AddNoteRequest {
+                note: basic_note("Japanese", "犬"),
+            })
+            .unwrap();
+        let error = anki
+            .request(AddNoteRequest {
+                note: basic_note("Japanese", "犬"),
+            })
+            .unwrap_err();
+        assert_eq!(error.anki_kind(), Some(AnkiErrorKind::DuplicateNote));
+        let error = anki
+            .request(AddNoteRequest {
+                note: basic_note("Korean", "개"),
+            })
+            .unwrap_err();
+        assert_eq!(error.anki_kind(), Some(AnkiErrorKind::DeckNotFound));
+
+        let found = anki
+            .request(FindNotesRequest {
+                query: "deck:Japanese tag:fake".to_string(),
+            })
+            .unwrap();
+        assert_eq!(found.0, vec![note]);
+        let info = anki
+            .request(NotesInfoRequest { notes: vec![note] })
+            .unwrap();
+        assert_eq!(info[0].fields["Front"].value, "犬");
+
+        let cards = anki
+            .request(FindCardsRequest {
+                query: format!("nid:{note}"),
+            })
+            .unwrap();
+        assert_eq!(cards.len(), 1);
+        anki.request(SuspendRequest {
+            cards: cards.clone(),
+        })
+        .unwrap();
+        let suspended = anki
+            .request(AreSuspendedRequest {
+                cards: cards.clone(),
+            })
+            .unwrap();
+        assert_eq!(suspended, vec![true]);
+        let found = anki
+            .request(FindCardsRequest {
+                query: "is:suspended".to_string(),
+            })
+            .unwrap();
+        assert_eq!(found, cards);
+
+        anki.request(DeleteNotesRequest { notes: vec![note] })
+            .unwrap();
+        let found = anki
+            .request(FindNotesRequest {
+                query: "deck:Japanese".to_string(),
+            })
+            .unwrap();
+        assert!(found.0.is_empty());
+    }
+
+    #[test]
+    fn test_default_deck_cant_be_deleted() {
+        let anki = FakeAnki::new();
+        let error = anki
+            .request(DeleteDecksRequest {
+                decks: vec!["Default".to_string()],
+                cards_too: true,
+            })
+            .unwrap_err();
+        assert!(matches!(error, Error::Anki(_)));
+        let decks = anki.request(DeckNamesRequest {}).unwrap();
+        assert_eq!(decks, vec!["Default"]);
+    }
+
+    #[test]
+    fn test_deck_config_workflow() {
+        let anki = FakeAnki::new();
+        let config = anki
+            .request(CloneDeckConfigIdRequest {
+                name: "Intense".to_string(),
+                clone_from: DeckConfigId(1),
+            })
+            .unwrap();
+        assert!(anki
+            .request(SetDeckConfigIdRequest {
+                decks: vec!["Default".to_string()],
+                config_id: config,
+            })
+            .unwrap());
+        let response = anki
+            .request(GetDeckConfigRequest {
+                deck: "Default".to_string(),
+            })
+            .unwrap();
+        assert_eq!(response.id, config);
+        assert_eq!(response.name, "Intense");
+
+        let mut edited = response;
+        edited.new.per_day = 50;
+        edited
+            .extra
+            .insert("desiredRetention".to_string(), json!(0.85));
+        assert!(anki
+            .request(SaveDeckConfigRequest {
+                config: edited.clone(),
+            })
+            .unwrap());
+        let response = anki
+            .request(GetDeckConfigRequest {
+                deck: "Default".to_string(),
+            })
+            .unwrap();
+        assert_eq!(response.new.per_day, 50);
+        assert_eq!(response.extra, edited.extra);
+    }
+
+    #[test]
+    fn test_create_model_from_definition() {
+        let anki = FakeAnki::new();
+        let definition = ModelDefinition::builder("Japanese")
+            .field("Expression")
+            .field("Meaning")
+            .template(
+                "Recognition",
+                "{{Expression}}",
+                "{{FrontSide}}<hr>{{Meaning}}",
+            )
+            .build()
+            .unwrap();
+        let model = anki.request(definition.create_request()).unwrap();
+        assert_eq!(model.name, "Japanese");
+        assert_eq!(model.fields[1].name, "Meaning");
+        assert_eq!(model.templates[0].front, "{{Expression}}");
+    }
+}
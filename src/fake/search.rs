@@ -0,0 +1,215 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use super::collection::{now, Card, Collection, DECK_SEPARATOR};
+use crate::search::{parse, CardState, Comparison, Flag, Property, Query};
+
+/// The separator between the parent and child names of a hierarchical tag.
+const TAG_SEPARATOR: &str = "::";
+
+/// Returns the ids of the cards matching the query. All queries of the
+/// [`search`](crate::search) module are supported, except for [`Query::Raw`] terms.
+pub(crate) fn find_cards(collection: &Collection, query: &str) -> Result<Vec<i64>, String> {
+    let query = parse(query).map_err(|error| format!("invalid search: {error}"))?;
+    check_supported(&query)?;
+    Ok(collection
+        .cards
+        .values()
+        .filter(|card| matches(collection, card, &query))
+        .map(|card| card.id)
+        .collect())
+}
+
+/// Returns the ids of the notes, which have at least one card matching the query.
+pub(crate) fn find_notes(collection: &Collection, query: &str) -> Result<Vec<i64>, String> {
+    let mut notes = find_cards(collection, query)?
+        .into_iter()
+        .filter_map(|card| collection.cards.get(&card).map(|card| card.note_id))
+        .collect::<Vec<_>>();
+    notes.sort_unstable();
+    notes.dedup();
+    Ok(notes)
+}
+
+fn check_supported(query: &Query) -> Result<(), String> {
+    match query {
+        Query::Raw(raw) => Err(format!("unsupported search: {raw}")),
+        Query::And(queries) | Query::Or(queries) => queries.iter().try_for_each(check_supported),
+        Query::Not(query) => check_supported(query),
+        _ => Ok(()),
+    }
+}
+
+fn matches(collection: &Collection, card: &Card, query: &Query) -> bool {
+    let Some(note) = collection.notes.get(&card.note_id) else {
+        return false;
+    };
+    let Some(model) = collection.models.get(&note.model_id) else {
+        return false;
+    };
+    let days_ago = |days: u32| (now() - i64::from(days) * 86_400) * 1000;
+    match query {
+        Query::Text(text) => {
+            let pattern = format!("*{}*", text.as_str());
+            note.fields.iter().any(|field| glob(&pattern, field))
+        }
+        Query::Deck(name) => collection.decks.get(&card.deck_id).is_some_and(|deck| {
+            glob(name.as_str(), &deck.name)
+                || glob(&format!("{}{DECK_SEPARATOR}*", name.as_str()), &deck.name)
+        }),
+        Query::Tag(tag) if tag.as_str().eq_ignore_ascii_case("none") => note.tags.is_empty(),
+        Query::Tag(tag) => note.tags.iter().any(|t| {
+            glob(tag.as_str(), t) || glob(&format!("{}{TAG_SEPARATOR}*", tag.as_str()), t)
+        }),
+        Query::NoteType(name) => glob(name.as_str(), &model.name),
+        Query::Card(template) => match template.as_str().parse::<usize>() {
+            Ok(number) => card.ord + 1 == number,
+            Err(_) => model
+                .templates
+                .get(card.ord)
+                .is_some_and(|t| glob(template.as_str(), &t.name)),
+        },
+        Query::Field { name, value } => model
+            .fields
+            .iter()
+            .zip(&note.fields)
+            .any(|(field, content)| glob(name.as_str(), field) && glob(value.as_str(), content)),
+        Query::Is(state) => match state {
+            CardState::New => card.card_type == 0,
+            CardState::Learn => matches!(card.queue, 1 | 3),
+            CardState::Review => matches!(card.card_type, 2 | 3),
+            CardState::Due => collection.is_due(card),
+            CardState::Suspended => card.queue == -1,
+            CardState::Buried => matches!(card.queue, -2 | -3),
+        },
+        // The fake doesn't support flags, so every card is unflagged.
+        Query::Flag(flag) => *flag == Flag::None,
+        Query::Prop {
+            property,
+            comparison,
+            value,
+        } => {
+            let actual = match property {
+                Property::Interval => card.interval as f64,
+                Property::Due if card.queue == 2 => (card.due - collection.today) as f64,
+                Property::Position if card.queue == 0 => card.due as f64,
+                Property::Due | Property::Position => return false,
+                Property::Reps => card.reps as f64,
+                Property::Lapses => card.lapses as f64,
+                Property::Ease => card.factor as f64 / 1000.0,
+            };
+            match comparison {
+                Comparison::Equal => (actual - value).abs() < f64::EPSILON,
+                Comparison::NotEqual => (actual - value).abs() >= f64::EPSILON,
+                Comparison::Less => actual < *value,
+                Comparison::LessOrEqual => actual <= *value,
+                Comparison::Greater => actual > *value,
+                Comparison::GreaterOrEqual => actual >= *value,
+            }
+        }
+        Query::Rated { days, ease } => collection.reviews.iter().any(|review| {
+            review.card_id == card.id
+                && review.id >= days_ago(*days)
+                && ease.is_none_or(|ease| review.ease == i64::from(ease))
+        }),
+        Query::Added(days) => card.id >= days_ago(*days),
+        Query::Edited(days) => note.modified * 1000 >= days_ago(*days),
+        Query::NoteIds(ids) => ids.iter().any(|id| id.get() == note.id),
+        Query::CardIds(ids) => ids.iter().any(|id| id.get() == card.id),
+        Query::And(queries) => queries.iter().all(|query| matches(collection, card, query)),
+        Query::Or(queries) => queries.iter().any(|query| matches(collection, card, query)),
+        Query::Not(query) => !matches(collection, card, query),
+        Query::Raw(_) => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pattern {
+    Any,
+    One,
+    Char(char),
+}
+
+/// Matches the text case-insensitively against the escaped pattern, in which `*` matches any
+/// sequence and `_` matches a single character.
+fn glob(pattern: &str, text: &str) -> bool {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars().flat_map(char::to_lowercase);
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '\\' => chars.next().map_or(Pattern::Char('\\'), Pattern::Char),
+            '*' => Pattern::Any,
+            '_' => Pattern::One,
+            c => Pattern::Char(c),
+        });
+    }
+    let text = text
+        .chars()
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match tokens.get(p) {
+            Some(Pattern::Any) => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(Pattern::One) => {
+                p += 1;
+                t += 1;
+            }
+            Some(Pattern::Char(c)) if *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[p..].iter().all(|token| *token == Pattern::Any)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob() {
+        assert!(glob("jap*", "Japanese"));
+        assert!(glob("*nes*", "Japanese"));
+        assert!(glob("japanese", "Japanese"));
+        assert!(glob("j_panese", "Japanese"));
+        assert!(!glob("jap", "Japanese"));
+        assert!(!glob(r"a\_b", "axb"));
+        assert!(glob(r"a\_b", "a_b"));
+        assert!(glob("*", ""));
+    }
+}
@@ -0,0 +1,256 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use serde_json::{json, Value};
+
+use super::FakeAnki;
+
+/// The address on which `AnkiConnect` listens by default.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8765";
+
+/// The maximum size of a request body, larger requests are answered with
+/// `413 Payload Too Large`.
+pub const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Accepts connections on the listener and answers every request with the fake. Every connection
+/// is handled on its own thread, and connections, which fail to be accepted, are skipped.
+pub fn serve(listener: &TcpListener, anki: &FakeAnki) -> ! {
+    loop {
+        // A failed or broken connection only affects the client, which opened it.
+        let Ok((stream, _)) = listener.accept() else {
+            continue;
+        };
+        let anki = anki.clone();
+        thread::spawn(move || {
+            let _ = handle_connection(stream, &anki);
+        });
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    /// The body of the request, [None] if it exceeds [`MAX_BODY_SIZE`] and wasn't read.
+    body: Option<Vec<u8>>,
+    keep_alive: bool,
+}
+
+/// Answers the requests of a connection, until the client closes it.
+fn handle_connection(stream: TcpStream, anki: &FakeAnki) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    while let Some(mut request) = read_request(&mut reader)? {
+        let (status, content_type, body) = match (request.method.as_str(), &request.body) {
+            (_, None) => {
+                // The rest of the body is still unread, so the connection can't be reused.
+                request.keep_alive = false;
+                ("413 Payload Too Large", "text/plain", String::new())
+            }
+            ("POST", Some(body)) => {
+                let response = match serde_json::from_slice::<Value>(body) {
+                    Ok(envelope) => anki.handle_envelope(&envelope),
+                    Err(error) => json!({ "result": null, "error": error.to_string() }),
+                };
+                ("200 OK", "application/json", response.to_string())
+            }
+            ("GET", _) => (
+                "200 OK",
+                "text/plain",
+                format!("AnkiConnect v.{}", crate::ANKI_CONNECT_VERSION),
+            ),
+            ("OPTIONS", _) => ("200 OK", "text/plain", String::new()),
+            _ => ("405 Method Not Allowed", "text/plain", String::new()),
+        };
+        write!(
+            writer,
+            "HTTP/1.1 {status}\r\n\
+             Content-Type: {content_type}\r\n\
+             Content-Length: {}\r\n\
+             Access-Control-Allow-Origin: *\r\n\
+             Access-Control-Allow-Headers: *\r\n\
+             Connection: {}\r\n\r\n{body}",
+            body.len(),
+            if request.keep_alive {
+                "keep-alive"
+            } else {
+                "close"
+            },
+        )?;
+        writer.flush()?;
+        if !request.keep_alive {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the next request of the connection, [None] if the client closed it.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<HttpRequest>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let mut keep_alive = parts.nth(1) != Some("HTTP/1.0");
+    let mut content_length = 0;
+    let mut chunked = false;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_lowercase().as_str() {
+            "content-length" => {
+                content_length = value
+                    .parse()
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            }
+            "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+            "connection" => keep_alive = !value.eq_ignore_ascii_case("close"),
+            _ => {}
+        }
+    }
+    let body = if chunked {
+        read_chunked(reader)?
+    } else if content_length > MAX_BODY_SIZE {
+        None
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        Some(body)
+    };
+    Ok(Some(HttpRequest {
+        method,
+        body,
+        keep_alive,
+    }))
+}
+
+/// Reads a chunked body, [None] if it exceeds [`MAX_BODY_SIZE`].
+fn read_chunked(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut body = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        if size == 0 {
+            // Skip the trailers and the final empty line.
+            loop {
+                line.clear();
+                if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                    return Ok(Some(body));
+                }
+            }
+        }
+        let start = body.len();
+        let Some(end) = start.checked_add(size).filter(|end| *end <= MAX_BODY_SIZE) else {
+            return Ok(None);
+        };
+        body.resize(end, 0);
+        reader.read_exact(&mut body[start..])?;
+        line.clear();
+        reader.read_line(&mut line)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn test_body_too_large() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(&listener, &FakeAnki::new()));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{{}}",
+            MAX_BODY_SIZE + 1
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 413 "), "{response}");
+    }
+
+    #[test]
+    fn test_chunk_size_overflow() {
+        let size = format!("{:x}", usize::MAX);
+        let body = format!("2\r\n{{}}\r\n{size}\r\n");
+        let mut reader = io::Cursor::new(body.into_bytes());
+        assert!(read_chunked(&mut reader).unwrap().is_none());
+    }
+
+    #[cfg(feature = "ureq_blocking")]
+    #[test]
+    fn test_client_against_fake_server() {
+        use crate::{prelude::*, Error};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let anki = FakeAnki::new();
+        anki.set_api_key(Some("secret".to_string()));
+        let server = anki.clone();
+        thread::spawn(move || serve(&listener, &server));
+
+        let client = UreqClient::builder().endpoint(&endpoint).build().unwrap();
+        let error = client.request(DeckNamesRequest {}).unwrap_err();
+        assert!(matches!(error, Error::Anki(_)));
+
+        let client = client.with_key("secret");
+        let id = client
+            .request(CreateDeckRequest {
+                deck: "Japanese".to_string(),
+            })
+            .unwrap();
+        let decks = client.request(DeckNamesAndIdsRequest {}).unwrap();
+        assert_eq!(decks["Japanese"], id);
+
+        client
+            .request(DeleteDecksRequest {
+                decks: vec!["Japanese".to_string()],
+                cards_too: true,
+            })
+            .unwrap();
+        let decks = client.request(DeckNamesAndIdsRequest {}).unwrap();
+        assert!(!decks.contains_key("Japanese"));
+    }
+}
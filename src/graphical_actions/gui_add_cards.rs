@@ -22,58 +22,93 @@
 * SOFTWARE.
 */
 
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{
+    ids::NoteId,
+    notes_actions::add_note::{AddNoteMedia, AddNoteNote, NoteFields},
+    AnkiRequest,
+};
 
 /// Parameters for adding cards using the Add Cards dialog.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GuiAddCardsRequest {
     /// The note to add using the Add Cards dialog.
-    pub note: GuiAddCardsNote,
+    ///
+    /// The dialog ignores its `options`.
+    pub note: AddNoteNote,
 }
 
 /// A note for adding cards using the Add Cards dialog.
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GuiAddCardsNote {
-    /// The deck name for the note.
-    pub deck_name: String,
-    /// The model name for the note.
-    pub model_name: String,
-    /// The fields of the note.
-    pub fields: GuiAddCardsNoteFields,
-    /// The tags of the note.
-    pub tags: Vec<String>,
-    /// The pictures attached to the note.
-    pub picture: Vec<GuiAddCardsNotePicture>,
-}
+#[deprecated(note = "use `AddNoteNote`, which is shared with \"addNote\"")]
+pub type GuiAddCardsNote = AddNoteNote;
 
 /// Fields of the note for adding cards using the Add Cards dialog.
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct GuiAddCardsNoteFields {
-    /// The "Text" field of the note.
-    #[serde(rename = "Text")]
-    pub text: String,
-    /// The "Extra" field of the note.
-    #[serde(rename = "Extra")]
-    pub extra: String,
-}
+#[deprecated(note = "use `NoteFields`, which supports every model instead of only \"Cloze\"")]
+pub type GuiAddCardsNoteFields = NoteFields;
 
 /// Picture attached to the note for adding cards using the Add Cards dialog.
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct GuiAddCardsNotePicture {
-    /// The URL of the picture.
-    pub url: String,
-    /// The filename of the picture.
-    pub filename: String,
-    /// The fields associated with the picture.
-    pub fields: Vec<String>,
-}
+#[deprecated(note = "use `AddNoteMedia`, which is shared with \"addNote\"")]
+pub type GuiAddCardsNotePicture = AddNoteMedia;
 
 impl AnkiRequest for GuiAddCardsRequest {
-    type Response = usize;
+    type Response = NoteId;
 
     const ACTION: &'static str = "guiAddCards";
     const VERSION: u8 = 6;
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::parse_response;
+
+    #[test]
+    fn test_gui_add_cards_to_json() {
+        let request = GuiAddCardsRequest {
+            note: AddNoteNote {
+                deck_name: "Default".to_string(),
+                model_name: "Cloze".to_string(),
+                fields: NoteFields::from([("Text", "The capital of Romania is {{c1::Bucharest}}")]),
+                tags: vec!["countries".to_string()],
+                picture: vec![AddNoteMedia {
+                    url: Some("https://example.com/flag.jpg".to_string()),
+                    filename: "romania.jpg".to_string(),
+                    fields: vec!["Extra".to_string()],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            json!({
+                "action": "guiAddCards",
+                "version": 6,
+                "params": {
+                    "note": {
+                        "deckName": "Default",
+                        "modelName": "Cloze",
+                        "fields": {"Text": "The capital of Romania is {{c1::Bucharest}}"},
+                        "tags": ["countries"],
+                        "picture": [{
+                            "url": "https://example.com/flag.jpg",
+                            "filename": "romania.jpg",
+                            "fields": ["Extra"],
+                        }],
+                    },
+                },
+            }),
+            request.to_json()
+        );
+    }
+
+    #[test]
+    fn test_gui_add_cards_response() {
+        let response =
+            parse_response::<GuiAddCardsRequest>(r#"{"result": 1496198395707, "error": null}"#)
+                .unwrap();
+        assert_eq!(NoteId(1_496_198_395_707), response);
+    }
+}
@@ -24,12 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{scheduling::Ease, AnkiRequest};
 
 /// Parameters for answering the current card.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GuiAnswerCardRequest {
-    pub ease: usize,
+    /// The button, which is used to answer the card.
+    pub ease: Ease,
 }
 
 impl AnkiRequest for GuiAnswerCardRequest {
@@ -26,7 +26,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::AnkiRequest;
+use crate::{ids::CardId, AnkiRequest};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GuiCurrentCardRequest;
@@ -52,7 +52,7 @@ pub struct GuiCurrentCardResponse {
     /// The template of the current card.
     pub template: String,
     /// The ID of the current card.
-    pub card_id: usize,
+    pub card_id: CardId,
     /// The buttons associated with the current card.
     pub buttons: Vec<isize>,
     /// The next reviews for the current card.
@@ -73,4 +73,5 @@ impl AnkiRequest for GuiCurrentCardRequest {
 
     const ACTION: &'static str = "guiCurrentCard";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -24,13 +24,13 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::NoteId, AnkiRequest};
 
 /// Parameters for editing a note using the Edit dialog.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GuiEditNoteRequest {
     /// The ID of the note to edit.
-    pub note: usize,
+    pub note: NoteId,
 }
 
 impl AnkiRequest for GuiEditNoteRequest {
@@ -24,14 +24,15 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::NoteId, AnkiRequest};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GuiSelectedNotesRequest;
 
 impl AnkiRequest for GuiSelectedNotesRequest {
-    type Response = Vec<usize>;
+    type Response = Vec<NoteId>;
 
     const ACTION: &'static str = "guiSelectedNotes";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -0,0 +1,157 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{
+    fmt::{self, Display, Formatter},
+    num::{ParseIntError, TryFromIntError},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::timestamps::Timestamp;
+
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        ///
+        /// Anki uses the creation time in milliseconds as id. It is serialized as a plain number.
+        #[derive(
+            Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub i64);
+
+        impl $name {
+            #[must_use]
+            pub const fn new(id: i64) -> Self {
+                Self(id)
+            }
+
+            #[must_use]
+            pub const fn get(self) -> i64 {
+                self.0
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(id: i64) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        /// Eases the transition from the former `usize` ids, failing for ids above [`i64::MAX`].
+        impl TryFrom<usize> for $name {
+            type Error = TryFromIntError;
+
+            fn try_from(id: usize) -> Result<Self, Self::Error> {
+                i64::try_from(id).map(Self)
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                Display::fmt(&self.0, f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIntError;
+
+            fn from_str(id: &str) -> Result<Self, Self::Err> {
+                id.parse().map(Self)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// The id of a card.
+    CardId
+);
+id_type!(
+    /// The id of a note.
+    NoteId
+);
+id_type!(
+    /// The id of a deck.
+    DeckId
+);
+id_type!(
+    /// The id of a model, which is called note type in Anki.
+    ModelId
+);
+id_type!(
+    /// The id of a deck config, which is called preset in Anki.
+    DeckConfigId
+);
+id_type!(
+    /// The id of a review in the review log, which is the time of the review.
+    ReviewId
+);
+
+impl ReviewId {
+    /// Returns the time of the review, which is stored in its id.
+    #[must_use]
+    pub fn reviewed_at<T: Timestamp>(self) -> Option<T> {
+        T::from_unix_millis(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_serde_as_number_and_map_key() {
+        assert_eq!(
+            serde_json::to_string(&CardId(1_700_000_000_000)).unwrap(),
+            "1700000000000"
+        );
+        let decks: HashMap<DeckId, String> = serde_json::from_str(r#"{"1": "Default"}"#).unwrap();
+        assert_eq!(decks[&DeckId(1)], "Default");
+    }
+
+    #[test]
+    fn test_review_time() {
+        let review: ReviewId = serde_json::from_str("1700000000123").unwrap();
+        let time = review.reviewed_at::<std::time::SystemTime>().unwrap();
+        assert_eq!(time.unix_millis(), 1_700_000_000_123);
+    }
+
+    #[test]
+    fn test_try_from_usize() {
+        assert_eq!(CardId::try_from(42_usize), Ok(CardId(42)));
+        #[cfg(target_pointer_width = "64")]
+        assert!(CardId::try_from(usize::MAX).is_err());
+    }
+}
@@ -23,24 +23,17 @@
 */
 #![allow(clippy::module_name_repetitions)]
 
-#[cfg(not(any(
-    feature = "reqwest_async",
-    feature = "reqwest_blocking",
-    feature = "ureq_blocking"
-)))]
-
-compile_error!("Please include EXACTLY ONE of the following client features: 'reqwest_async', 'reqwest_blocking' or 'ureq_blocking'");
-
-#[cfg(any(
-    all(feature = "reqwest_async", feature = "reqwest_blocking"),
-    all(feature = "reqwest_async", feature = "ureq_blocking"),
-    all(feature = "reqwest_blocking", feature = "ureq_blocking")
-))]
-compile_error!("Please include ONLY ONE of the following client features: 'reqwest_async', 'reqwest_blocking' or 'ureq_blocking'");
+use std::{fmt, marker::PhantomData, sync::Arc, time::Duration};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
-use thiserror::Error;
+
+pub use crate::error::{AnkiError, AnkiErrorKind, Error};
+use crate::miscellaneous_actions::request_permission::RequestPermissionRequst;
+use crate::{
+    retry::RetryPolicy,
+    transport::{AsyncTransport, BuildTransport, Transport, TransportOptions, TransportRequest},
+};
 
 /// Module containing card-related actions for `AnkiConnect`.
 pub mod card_actions;
@@ -48,6 +41,8 @@ pub mod card_actions;
 pub mod deck_actions;
 /// Module containing graphical-related actions for `AnkiConnect`.
 pub mod graphical_actions;
+/// Module containing media-related actions for `AnkiConnect`.
+pub mod media_actions;
 /// Module containing statistic-related actions for `AnkiConnect`.
 pub mod miscellaneous_actions;
 /// Module containing model-related actions for `AnkiConnect`.
@@ -57,32 +52,29 @@ pub mod notes_actions;
 /// Module containing statistic-related actions for `AnkiConnect`.
 pub mod statistic_actions;
 
+/// Module containing the errors which can occur while sending requests.
+pub mod error;
+/// Module containing an in-memory fake of Anki, which can be used in other projects.
+pub mod fake;
+/// Module containing the typed ids of cards, notes, decks, models and deck configs.
+pub mod ids;
 /// Module containing mockable client which can be used in other projects.
 pub mod mock;
 /// Module containing every module which could be useful;
 pub mod prelude;
-
-/// Represents the possible errors that can occur during the execution of the `anki_connect_send` function.
-#[derive(Debug, Error)]
-pub enum Error {
-    #[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking"))]
-    /// Error indicating a failure in sending the request with `reqwest`.
-    #[error("send request with reqwest failed")]
-    Reqwest(#[from] reqwest::Error),
-
-    #[cfg(feature = "ureq_blocking")]
-    /// Error indicating a failure in sending the request with `ureq`.
-    #[error("send request with ureq failed")]
-    Ureq(#[from] Box<ureq::Error>),
-
-    /// Error indicating a deserialization error.
-    #[error("deserialization error")]
-    Serde(#[from] std::io::Error),
-
-    /// Error indicating that Anki returned an unexpected error message.
-    #[error("anki returned an unexpected error: {0}")]
-    Anki(String),
-}
+/// Module containing the policies for retrying failed requests.
+pub mod retry;
+/// Module containing the types for the scheduling state of cards and their reviews.
+pub mod scheduling;
+/// Module containing the builder and the parser for Anki search queries.
+pub mod search;
+/// Module containing the conversions of the timestamps and dates used by Anki.
+pub mod timestamps;
+/// Module containing the transports, which send the requests over HTTP.
+pub mod transport;
+
+/// The version of the `AnkiConnect` API, which is used by every request.
+pub const ANKI_CONNECT_VERSION: usize = 6;
 
 /// A specialized `Result` type used in the context of `AnkiConnect` requests.
 ///
@@ -90,70 +82,381 @@ pub enum Error {
 type Result<R> = std::result::Result<R, Error>;
 
 /// Represents a response received from `AnkiConnect` API.
-#[derive(Deserialize)]
-struct AnkiConnectResponse<R> {
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AnkiConnectResponse<R> {
     /// The result of the API call, if any.
-    result: Option<R>,
+    pub result: Option<R>,
 
     /// The error message, if an error occurred.
-    error: Option<String>,
+    pub error: Option<String>,
 }
 
-pub struct AnkiClient<'a> {
-    pub endpoint: &'a str,
+impl<R: Default> AnkiConnectResponse<R> {
+    /// Converts the response into a [`Result`], falling back to the default value if `AnkiConnect`
+    /// returned neither a result nor an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Anki`] if `AnkiConnect` returned an error message for the given action.
+    pub fn into_result(self, action: &'static str) -> Result<R> {
+        if let Some(error) = self.error {
+            Err(AnkiError::new(action, error).into())
+        } else if let Some(result) = self.result {
+            Ok(result)
+        } else {
+            Ok(Default::default())
+        }
+    }
+}
+
+/// Parses the raw body of a response to the given request.
+fn parse_response<Request: AnkiRequest>(body: &str) -> Result<Request::Response> {
+    serde_json::from_str::<AnkiConnectResponse<Request::Response>>(body)
+        .map_err(|error| Error::serde(Request::ACTION, body, error))?
+        .into_result(Request::ACTION)
+}
 
-    #[cfg(feature = "ureq_blocking")]
-    pub agent: ureq::Agent,
+/// The endpoint on which `AnkiConnect` listens by default.
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:8765";
 
-    #[cfg(feature = "reqwest_async")]
-    pub client: reqwest::Client,
+/// The client which sends the requests to `AnkiConnect` with the given transport. It is cheap to
+/// clone, because the built-in transports are reference counted.
+#[derive(Debug, Clone)]
+pub struct AnkiClient<T> {
+    pub endpoint: String,
 
-    #[cfg(feature = "reqwest_blocking")]
-    pub client: reqwest::blocking::Client,
+    /// The API key, which is sent with every request, if `AnkiConnect` requires one.
+    pub key: Option<String>,
+
+    /// Headers, which are sent with every request.
+    pub headers: Vec<(String, String)>,
+
+    /// The policy for retrying idempotent requests, no request is retried if [None].
+    pub retry_policy: Option<Arc<dyn RetryPolicy>>,
+
+    /// The transport, which sends the requests over HTTP.
+    pub transport: T,
 }
 
-impl<'a> AnkiClient<'a> {
+/// A client, which sends the requests with [`ureq`](transport::UreqTransport).
+#[cfg(feature = "ureq_blocking")]
+pub type UreqClient = AnkiClient<transport::UreqTransport>;
+
+/// A client, which sends the requests with the blocking
+/// [`reqwest`](transport::ReqwestBlockingTransport) client.
+#[cfg(feature = "reqwest_blocking")]
+pub type ReqwestBlockingClient = AnkiClient<transport::ReqwestBlockingTransport>;
+
+/// A client, which sends the requests with the asynchronous
+/// [`reqwest`](transport::ReqwestAsyncTransport) client.
+#[cfg(feature = "reqwest_async")]
+pub type ReqwestAsyncClient = AnkiClient<transport::ReqwestAsyncTransport>;
+
+impl<T: Default> AnkiClient<T> {
+    /// Creates a client with the default configuration of the transport, e.g.
+    /// `UreqClient::new("http://localhost:8765")`.
     #[must_use]
-    pub fn new(endpoint: &'a str) -> Self {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self::with_transport(endpoint, T::default())
+    }
+}
+
+impl<T: Transport + Default> AnkiClient<T> {
+    /// Creates a client and requests the permission to use the API, see
+    /// [`AnkiClient::ensure_permission`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or one of the checks doesn't pass.
+    pub fn connect(endpoint: impl Into<String>, key: Option<String>) -> Result<Self> {
+        let client = Self {
+            key,
+            ..Self::new(endpoint)
+        };
+        client.ensure_permission()?;
+        Ok(client)
+    }
+}
+
+impl<T: AsyncTransport + Default> AnkiClient<T> {
+    /// Creates a client and requests the permission to use the API, see
+    /// [`AnkiClient::ensure_permission_async`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or one of the checks doesn't pass.
+    pub async fn connect_async(endpoint: impl Into<String>, key: Option<String>) -> Result<Self> {
+        let client = Self {
+            key,
+            ..Self::new(endpoint)
+        };
+        client.ensure_permission_async().await?;
+        Ok(client)
+    }
+}
+
+impl<T> AnkiClient<T> {
+    /// Creates a client, which sends the requests with the given transport.
+    #[must_use]
+    pub fn with_transport(endpoint: impl Into<String>, transport: T) -> Self {
         Self {
-            endpoint,
+            endpoint: endpoint.into(),
+            key: None,
+            headers: Vec::new(),
+            retry_policy: None,
+            transport,
+        }
+    }
 
-            #[cfg(feature = "ureq_blocking")]
-            agent: ureq::agent(),
+    /// Returns a builder to configure the client, e.g. `UreqClient::builder()`.
+    #[must_use]
+    pub fn builder() -> AnkiClientBuilder<T> {
+        AnkiClientBuilder::default()
+    }
 
-            #[cfg(feature = "reqwest_async")]
-            client: reqwest::Client::new(),
+    /// Sets the API key, which is sent with every request.
+    #[must_use]
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
 
-            #[cfg(feature = "reqwest_blocking")]
-            client: reqwest::blocking::Client::new(),
+    /// Wraps the request into the JSON envelope, which includes the API key if there is one.
+    fn envelope<Request: AnkiRequest>(&self, params: &Request) -> Value {
+        let mut json = params.to_json();
+        if let Some(key) = &self.key {
+            json["key"] = Value::String(key.clone());
         }
+        json
+    }
+
+    /// Returns the request for the transport.
+    fn transport_request<'a>(&'a self, body: &'a Value) -> TransportRequest<'a> {
+        TransportRequest {
+            endpoint: &self.endpoint,
+            headers: &self.headers,
+            body,
+        }
+    }
+
+    /// Returns the delay before the request is sent again, if it failed and may be retried.
+    fn retry_delay<Request: AnkiRequest>(
+        &self,
+        attempt: u32,
+        result: &Result<Request::Response>,
+    ) -> Option<Duration> {
+        match (result, &self.retry_policy) {
+            (Err(error), Some(policy)) if Request::IDEMPOTENT => policy.retry_delay(attempt, error),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Transport> AnkiClient<T> {
+    /// Requests the permission to use the API. This ensures early that Anki is reachable, that
+    /// the permission is granted, that an API key is given if one is required and that the
+    /// version of `AnkiConnect` is supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or one of the checks above doesn't pass.
+    pub fn ensure_permission(&self) -> Result<()> {
+        self.request(RequestPermissionRequst)?
+            .check(self.key.is_some())
+    }
+}
+
+impl<T: AsyncTransport> AnkiClient<T> {
+    /// Requests the permission to use the API, like [`AnkiClient::ensure_permission`] for
+    /// asynchronous transports.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or one of the checks doesn't pass.
+    pub async fn ensure_permission_async(&self) -> Result<()> {
+        self.request(RequestPermissionRequst)
+            .await?
+            .check(self.key.is_some())
     }
 }
 
-impl<'a> Default for AnkiClient<'a> {
+impl<T: Default> Default for AnkiClient<T> {
+    #[allow(unused_attributes)]
     #[must_use]
     fn default() -> Self {
-        Self::new("http://localhost:8765")
+        Self::new(DEFAULT_ENDPOINT)
+    }
+}
+
+/// Builder for an [`AnkiClient`] with the transport `T`, which works the same for every
+/// transport.
+pub struct AnkiClientBuilder<T> {
+    endpoint: String,
+    key: Option<String>,
+    headers: Vec<(String, String)>,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    options: TransportOptions,
+    transport: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for AnkiClientBuilder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnkiClientBuilder")
+            .field("endpoint", &self.endpoint)
+            .field("key", &self.key)
+            .field("headers", &self.headers)
+            .field("retry_policy", &self.retry_policy)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<T> Clone for AnkiClientBuilder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            endpoint: self.endpoint.clone(),
+            key: self.key.clone(),
+            headers: self.headers.clone(),
+            retry_policy: self.retry_policy.clone(),
+            options: self.options.clone(),
+            transport: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for AnkiClientBuilder<T> {
+    fn default() -> Self {
+        Self {
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            key: None,
+            headers: Vec::new(),
+            retry_policy: None,
+            options: TransportOptions::default(),
+            transport: PhantomData,
+        }
     }
 }
 
-#[maybe_async::sync_impl]
+impl<T> AnkiClientBuilder<T> {
+    /// Sets the endpoint, defaults to [`DEFAULT_ENDPOINT`].
+    #[must_use]
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Sets the API key, which is sent with every request.
+    #[must_use]
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Sets the timeout of a whole request, including connecting and reading the response.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a header, which is sent with every request.
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the `User-Agent` header.
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.options.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the policy for retrying idempotent requests, e.g.
+    /// [`ExponentialBackoff`](retry::ExponentialBackoff).
+    #[must_use]
+    pub fn retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+
+    /// Builds the client with the transport `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying HTTP client can't be built.
+    pub fn build(self) -> Result<AnkiClient<T>>
+    where
+        T: BuildTransport,
+    {
+        self.build_with()
+    }
+
+    /// Builds the client with another type of transport, e.g.
+    /// `builder.build_with::<ReqwestAsyncTransport>()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying HTTP client can't be built.
+    pub fn build_with<U: BuildTransport>(self) -> Result<AnkiClient<U>> {
+        let transport = U::build(&self.options)?;
+        Ok(self.build_with_transport(transport))
+    }
+
+    /// Builds the client with an already configured transport. The timeout and the
+    /// `User-Agent` are ignored, because they are part of the transport.
+    #[must_use]
+    pub fn build_with_transport<U>(self, transport: U) -> AnkiClient<U> {
+        AnkiClient {
+            endpoint: self.endpoint,
+            key: self.key,
+            headers: self.headers,
+            retry_policy: self.retry_policy,
+            transport,
+        }
+    }
+}
+
+/// Sends requests and blocks until the response is received.
 pub trait AnkiRequestable<Request: AnkiRequest> {
     fn request(&self, params: Request) -> Result<Request::Response>;
 }
 
-#[maybe_async::async_impl]
+/// Sends requests asynchronously.
 #[async_trait::async_trait]
-pub trait AnkiRequestable<Request: AnkiRequest + Send> {
+pub trait AsyncAnkiRequestable<Request: AnkiRequest + Send> {
     async fn request(&self, params: Request) -> Result<Request::Response>;
 }
 
+/// Sends a request, picking the matching bound of a client, which implements
+/// [`AnkiRequestable`] for several requests.
+pub(crate) fn send<R: AnkiRequest, C: AnkiRequestable<R>>(
+    client: &C,
+    request: R,
+) -> Result<R::Response> {
+    client.request(request)
+}
+
+/// Sends a request asynchronously, picking the matching bound of a client, which implements
+/// [`AsyncAnkiRequestable`] for several requests.
+pub(crate) async fn send_async<R: AnkiRequest + Send, C: AsyncAnkiRequestable<R> + Sync>(
+    client: &C,
+    request: R,
+) -> Result<R::Response> {
+    client.request(request).await
+}
+
 pub trait AnkiRequest: std::fmt::Debug + Serialize {
     type Response: Default + DeserializeOwned;
 
     const ACTION: &'static str;
     const VERSION: u8;
 
+    /// Whether sending the request again doesn't change the outcome, which allows the client to
+    /// retry it. Only read-only requests are marked as idempotent.
+    const IDEMPOTENT: bool = false;
+
     fn to_json(&self) -> Value {
         if json!(self).is_null() {
             json!({
@@ -170,63 +473,136 @@ pub trait AnkiRequest: std::fmt::Debug + Serialize {
     }
 }
 
-#[maybe_async::sync_impl]
-impl<'a, Request: AnkiRequest> AnkiRequestable<Request> for AnkiClient<'a> {
+impl<Request: AnkiRequest, T: Transport> AnkiRequestable<Request> for AnkiClient<T> {
     fn request(&self, params: Request) -> Result<Request::Response> {
-        #[cfg(feature = "ureq_blocking")]
-        let response: AnkiConnectResponse<Request::Response> = self
-            .agent
-            .post(self.endpoint)
-            .send_json(params.to_json())
-            .map_err(|error| Error::Ureq(Box::new(error)))?
-            .into_json::<AnkiConnectResponse<Request::Response>>()?;
-
-        #[cfg(feature = "reqwest_blocking")]
-        let response: AnkiConnectResponse<Request::Response> = {
-            self.client
-                .post(self.endpoint)
-                .json(&params.to_json())
-                .send()
-                .map_err(Error::Reqwest)?
-                .json::<AnkiConnectResponse<Request::Response>>()
-                .map_err(Error::Reqwest)
-        }?;
-
-        if let Some(error) = response.error {
-            Err(Error::Anki(error))
-        } else if let Some(result) = response.result {
-            Ok(result)
-        } else {
-            Ok(Default::default())
+        let json = self.envelope(&params);
+        let mut attempt = 1;
+        loop {
+            let result = self
+                .transport
+                .send(self.transport_request(&json))
+                .map_err(|error| error.with_action(Request::ACTION))
+                .and_then(|body| parse_response::<Request>(&body));
+            match self.retry_delay::<Request>(attempt, &result) {
+                Some(delay) => std::thread::sleep(delay),
+                None => return result,
+            }
+            attempt += 1;
         }
     }
 }
 
-#[maybe_async::async_impl]
 #[async_trait::async_trait]
-impl<'a, Request: AnkiRequest + Send + 'a> AnkiRequestable<Request> for AnkiClient<'a> {
+impl<Request, T> AsyncAnkiRequestable<Request> for AnkiClient<T>
+where
+    Request: AnkiRequest + Send + 'static,
+    T: AsyncTransport,
+{
     async fn request(&self, params: Request) -> Result<Request::Response> {
-        let json = params.to_json();
-
-        #[cfg(feature = "reqwest_async")]
-        let response: AnkiConnectResponse<Request::Response> = {
-            self.client
-                .post(self.endpoint)
-                .json(&json)
-                .send()
-                .await
-                .map_err(Error::Reqwest)?
-                .json::<AnkiConnectResponse<Request::Response>>()
-                .await
-                .map_err(Error::Reqwest)
-        }?;
-
-        if let Some(error) = response.error {
-            Err(Error::Anki(error))
-        } else if let Some(result) = response.result {
-            Ok(result)
-        } else {
-            Ok(Default::default())
+        let json = self.envelope(&params);
+        let mut attempt = 1;
+        loop {
+            let delay = {
+                let result = match self.transport.send(self.transport_request(&json)).await {
+                    Ok(body) => parse_response::<Request>(&body),
+                    Err(error) => Err(error.with_action(Request::ACTION)),
+                };
+                match self.retry_delay::<Request>(attempt, &result) {
+                    Some(delay) => delay,
+                    None => return result,
+                }
+            };
+            self.transport.sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::deck_actions::deck_names::DeckNamesRequest;
+
+    /// A request, which was sent by the [`CapturingTransport`].
+    struct CapturedRequest {
+        endpoint: String,
+        headers: Vec<(String, String)>,
+        body: Value,
+    }
+
+    /// Keeps the options it was built with and the requests it was asked to send.
+    #[derive(Default)]
+    struct CapturingTransport {
+        options: TransportOptions,
+        requests: Mutex<Vec<CapturedRequest>>,
+    }
+
+    impl BuildTransport for CapturingTransport {
+        fn build(options: &TransportOptions) -> Result<Self> {
+            Ok(Self {
+                options: options.clone(),
+                ..Default::default()
+            })
         }
     }
+
+    impl Transport for CapturingTransport {
+        fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+            self.requests.lock().unwrap().push(CapturedRequest {
+                endpoint: request.endpoint.to_string(),
+                headers: request.headers.to_vec(),
+                body: request.body.clone(),
+            });
+            Ok(json!({ "result": ["Default"], "error": null }).to_string())
+        }
+    }
+
+    #[test]
+    fn test_builder_configures_the_transport() {
+        let client = AnkiClient::<CapturingTransport>::builder()
+            .key("secret")
+            .timeout(Duration::from_secs(5))
+            .user_agent("my-app")
+            .header("X-Request-Source", "tests")
+            .build()
+            .unwrap();
+        assert_eq!(
+            TransportOptions {
+                timeout: Some(Duration::from_secs(5)),
+                user_agent: Some("my-app".to_string()),
+            },
+            client.transport.options
+        );
+
+        assert_eq!(vec!["Default"], client.request(DeckNamesRequest).unwrap());
+        let requests = client.transport.requests.lock().unwrap();
+        assert_eq!(DEFAULT_ENDPOINT, requests[0].endpoint);
+        assert_eq!(
+            vec![("X-Request-Source".to_string(), "tests".to_string())],
+            requests[0].headers
+        );
+        assert_eq!(
+            json!({ "action": "deckNames", "version": 6, "key": "secret" }),
+            requests[0].body
+        );
+    }
+
+    #[test]
+    fn test_builder_endpoint() {
+        let client = AnkiClient::<CapturingTransport>::builder()
+            .endpoint("http://127.0.0.1:9876")
+            .build()
+            .unwrap();
+        client.request(DeckNamesRequest).unwrap();
+        assert_eq!(
+            "http://127.0.0.1:9876",
+            client.transport.requests.lock().unwrap()[0].endpoint
+        );
+        assert_eq!(
+            DEFAULT_ENDPOINT,
+            AnkiClient::<CapturingTransport>::default().endpoint
+        );
+    }
 }
@@ -0,0 +1,50 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use serde::Serialize;
+
+use crate::AnkiRequest;
+
+/// Parameters for the "`getMediaFilesNames`" action in `AnkiConnect`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GetMediaFilesNamesRequest {
+    /// The glob pattern the file names have to match, e.g. `_hell*.txt`.
+    pub pattern: String,
+}
+
+impl Default for GetMediaFilesNamesRequest {
+    fn default() -> Self {
+        Self {
+            pattern: "*".to_string(),
+        }
+    }
+}
+
+impl AnkiRequest for GetMediaFilesNamesRequest {
+    type Response = Vec<String>;
+
+    const ACTION: &'static str = "getMediaFilesNames";
+    const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
+}
@@ -0,0 +1,69 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use base64::{engine::general_purpose::STANDARD, DecodeError, Engine};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::AnkiRequest;
+
+/// Parameters for the "`retrieveMediaFile`" action in `AnkiConnect`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RetrieveMediaFileRequest {
+    /// The name of the file inside the media folder.
+    pub filename: String,
+}
+
+/// The base64 encoded content of the file, [None] if the file does not exist.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct RetrieveMediaFileResponse(pub Option<String>);
+
+impl RetrieveMediaFileResponse {
+    /// Decodes the base64 encoded content of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content isn't valid base64.
+    pub fn decode(&self) -> Option<Result<Vec<u8>, DecodeError>> {
+        self.0.as_ref().map(|data| STANDARD.decode(data))
+    }
+}
+
+impl<'de> Deserialize<'de> for RetrieveMediaFileResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // `AnkiConnect` returns `false` instead of `null`, if the file does not exist.
+        Ok(match Value::deserialize(deserializer)? {
+            Value::String(data) => Self(Some(data)),
+            _ => Self(None),
+        })
+    }
+}
+
+impl AnkiRequest for RetrieveMediaFileRequest {
+    type Response = RetrieveMediaFileResponse;
+
+    const ACTION: &'static str = "retrieveMediaFile";
+    const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
+}
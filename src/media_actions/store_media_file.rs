@@ -0,0 +1,136 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{io, path::Path};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+
+use crate::AnkiRequest;
+
+/// Parameters for the "`storeMediaFile`" action in `AnkiConnect`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreMediaFileRequest {
+    /// The name of the file inside the media folder.
+    pub filename: String,
+    /// Where the content of the file comes from.
+    #[serde(flatten)]
+    pub source: StoreMediaFileSource,
+    /// Deletes an existing file with the same name, otherwise Anki picks a non-conflicting name.
+    pub delete_existing: bool,
+}
+
+/// The source of the file which should be stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StoreMediaFileSource {
+    /// The base64 encoded content of the file.
+    Data(String),
+    /// The absolute path to the file, which has to be readable by Anki.
+    Path(String),
+    /// The URL to download the file from.
+    Url(String),
+}
+
+impl Default for StoreMediaFileSource {
+    fn default() -> Self {
+        Self::Data(String::new())
+    }
+}
+
+impl Default for StoreMediaFileRequest {
+    fn default() -> Self {
+        Self {
+            filename: String::new(),
+            source: StoreMediaFileSource::default(),
+            delete_existing: true,
+        }
+    }
+}
+
+impl StoreMediaFileRequest {
+    /// Creates a request which stores the given bytes, encoded as base64.
+    #[must_use]
+    pub fn from_bytes(filename: impl Into<String>, bytes: impl AsRef<[u8]>) -> Self {
+        Self {
+            filename: filename.into(),
+            source: StoreMediaFileSource::Data(STANDARD.encode(bytes)),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a request which reads the local file and sends its content, encoded as base64.
+    /// Unlike [`StoreMediaFileSource::Path`], the file doesn't need to be readable by Anki.
+    /// The file name inside the media folder is the name of the local file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or the path doesn't have a file name.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} has no valid file name", path.display()),
+                )
+            })?;
+        Ok(Self::from_bytes(filename, std::fs::read(path)?))
+    }
+}
+
+impl AnkiRequest for StoreMediaFileRequest {
+    /// The name of the stored file, which differs from the requested one if Anki had to rename it.
+    type Response = String;
+
+    const ACTION: &'static str = "storeMediaFile";
+    const VERSION: u8 = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_store_media_file_to_json() {
+        let request = StoreMediaFileRequest::from_bytes("_hello.txt", "Hello, world!");
+        assert_eq!(
+            json!({
+                "action": "storeMediaFile",
+                "version": 6,
+                "params": {
+                    "filename": "_hello.txt",
+                    "data": "SGVsbG8sIHdvcmxkIQ==",
+                    "deleteExisting": true,
+                },
+            }),
+            request.to_json()
+        );
+    }
+}
@@ -44,4 +44,5 @@ impl AnkiRequest for ApiReflectRequest {
 
     const ACTION: &'static str = "apiReflect";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -25,6 +25,7 @@
 /// Gets information about the `AnkiConnect` APIs available. The request supports the following params:
 /// - [`scopes`](ApiReflectRequest::scopes) - An array of scopes to get reflection information about. The only currently supported value is `"actions"`.
 /// - [`actions`](ApiReflectRequest::actions) - Either `null` or an array of API method names to check for. If the value is `null`, the result will list all of the available API actions. If the value is an array of strings, the result will only contain actions which were in this array.
+///
 /// The result will contain a list of which scopes were used and a value for each scope. For example, the `"actions"` scope will contain a `"actions"` property which contains a list of supported action names.
 pub mod api_reflect;
 
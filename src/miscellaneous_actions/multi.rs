@@ -22,19 +22,190 @@
 * SOFTWARE.
 */
 
-use serde::Serialize;
+use std::marker::PhantomData;
 
-use crate::AnkiRequest;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
 
-/// Parameters for the "multi" action.
+use crate::{AnkiConnectResponse, AnkiError, AnkiRequest, Error, Result};
+
+/// Parameters for the "multi" action, where every action is of the same type.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct MultiRequest<T: AnkiRequest> {
+    #[serde(rename = "actions", serialize_with = "serialize_actions")]
     pub requests: Vec<T>,
 }
 
 impl<T: AnkiRequest> AnkiRequest for MultiRequest<T> {
-    type Response = Vec<T::Response>;
+    /// The response of every action, in the same order as the requests.
+    type Response = Vec<AnkiConnectResponse<T::Response>>;
+
+    const ACTION: &'static str = "multi";
+    const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = T::IDEMPOTENT;
+}
+
+/// Serializes every action with its `action`, `version` and `params`, like a single request.
+fn serialize_actions<T: AnkiRequest, S: Serializer>(
+    requests: &[T],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_seq(requests.iter().map(AnkiRequest::to_json))
+}
+
+/// Parameters for the "multi" action, where the actions can be of different types.
+///
+/// Every pushed request returns a [`MultiSlot`], which is used to get the typed response of the
+/// action out of the [`MultiBatchResponse`].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MultiBatch {
+    actions: Vec<Value>,
+}
+
+impl MultiBatch {
+    /// Creates an empty batch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the request to the batch and returns the slot of its response.
+    pub fn push<R: AnkiRequest>(&mut self, request: &R) -> MultiSlot<R> {
+        self.actions.push(request.to_json());
+        MultiSlot {
+            index: self.actions.len() - 1,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of actions in the batch.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns [true] if the batch doesn't contain any actions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+impl AnkiRequest for MultiBatch {
+    type Response = MultiBatchResponse;
 
     const ACTION: &'static str = "multi";
     const VERSION: u8 = 6;
 }
+
+/// The position of a request inside a [`MultiBatch`].
+#[derive(Debug)]
+pub struct MultiSlot<R: AnkiRequest> {
+    index: usize,
+    phantom: PhantomData<fn() -> R>,
+}
+
+impl<R: AnkiRequest> Clone for MultiSlot<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R: AnkiRequest> Copy for MultiSlot<R> {}
+
+impl<R: AnkiRequest> MultiSlot<R> {
+    /// Returns the position of the request inside the batch.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// The responses of every action of a [`MultiBatch`], in the same order as the requests.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MultiBatchResponse(pub Vec<AnkiConnectResponse<Value>>);
+
+impl MultiBatchResponse {
+    /// Returns the typed response of the action in the given slot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this action failed or if its response can't be deserialized. The other
+    /// actions of the batch are not affected. Returns [`Error::MissingMultiResponse`] if the
+    /// response doesn't contain a result for the slot.
+    pub fn get<R: AnkiRequest>(&self, slot: MultiSlot<R>) -> Result<R::Response> {
+        let response = self.0.get(slot.index).ok_or(Error::MissingMultiResponse {
+            action: R::ACTION,
+            index: slot.index,
+        })?;
+        if let Some(error) = &response.error {
+            return Err(AnkiError::new(R::ACTION, error.clone()).into());
+        }
+        match &response.result {
+            Some(result) => R::Response::deserialize(result)
+                .map_err(|error| Error::serde(R::ACTION, &result.to_string(), error)),
+            None => Ok(Default::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        card_actions::find_cards::FindCardsRequest, deck_actions::deck_names::DeckNamesRequest,
+        ids::CardId, AnkiErrorKind,
+    };
+
+    #[test]
+    fn test_multi_batch() {
+        let mut batch = MultiBatch::new();
+        let cards = batch.push(&FindCardsRequest {
+            query: "deck:current".to_string(),
+        });
+        let decks = batch.push(&DeckNamesRequest);
+        assert_eq!(
+            json!({
+                "action": "multi",
+                "version": 6,
+                "params": {
+                    "actions": [
+                        {
+                            "action": "findCards",
+                            "version": 6,
+                            "params": { "query": "deck:current" },
+                        },
+                        { "action": "deckNames", "version": 6 },
+                    ],
+                },
+            }),
+            batch.to_json()
+        );
+
+        let response: MultiBatchResponse = serde_json::from_value(json!([
+            { "result": [1, 2, 3], "error": null },
+            { "result": null, "error": "collection is not available" },
+        ]))
+        .unwrap();
+        assert_eq!(
+            vec![CardId(1), CardId(2), CardId(3)],
+            response.get(cards).unwrap()
+        );
+        let Err(Error::Anki(error)) = response.get(decks) else {
+            panic!("expected an anki error");
+        };
+        assert_eq!("deckNames", error.action);
+        assert_eq!(AnkiErrorKind::CollectionNotAvailable, error.kind);
+
+        let response = MultiBatchResponse(response.0[..1].to_vec());
+        assert!(matches!(
+            response.get(decks),
+            Err(Error::MissingMultiResponse {
+                action: "deckNames",
+                index: 1,
+            })
+        ));
+    }
+}
@@ -24,7 +24,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::AnkiRequest;
+use crate::{AnkiRequest, Error, Result, ANKI_CONNECT_VERSION};
 
 /// Parameters for the "requestPermission" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
@@ -34,11 +34,37 @@ pub struct RequestPermissionRequst;
 #[serde(rename_all = "camelCase")]
 pub struct RequestPermissionResponse {
     pub permission: Permission,
+    /// Only returned if the permission is granted.
+    #[serde(default)]
     pub require_api_key: bool,
+    /// Only returned if the permission is granted.
+    #[serde(default)]
     pub version: usize,
 }
 
+impl RequestPermissionResponse {
+    /// Checks that the permission is granted, that an API key is given if one is required and
+    /// that the version of `AnkiConnect` is supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PermissionDenied`], [`Error::ApiKeyRequired`] or
+    /// [`Error::UnsupportedVersion`] if one of the checks doesn't pass.
+    pub fn check(&self, has_key: bool) -> Result<()> {
+        if self.permission == Permission::Denied {
+            Err(Error::PermissionDenied)
+        } else if self.require_api_key && !has_key {
+            Err(Error::ApiKeyRequired)
+        } else if self.version < ANKI_CONNECT_VERSION {
+            Err(Error::UnsupportedVersion(self.version))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Permission {
     #[default]
     Denied,
@@ -50,4 +76,37 @@ impl AnkiRequest for RequestPermissionRequst {
 
     const ACTION: &'static str = "requestPermission";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_request_permission_check() {
+        let denied: RequestPermissionResponse =
+            serde_json::from_value(json!({ "permission": "denied" })).unwrap();
+        assert!(matches!(denied.check(true), Err(Error::PermissionDenied)));
+
+        let granted: RequestPermissionResponse = serde_json::from_value(json!({
+            "permission": "granted",
+            "requireApiKey": true,
+            "version": 6,
+        }))
+        .unwrap();
+        assert!(matches!(granted.check(false), Err(Error::ApiKeyRequired)));
+        assert!(granted.check(true).is_ok());
+
+        let outdated = RequestPermissionResponse {
+            version: 5,
+            ..granted
+        };
+        assert!(matches!(
+            outdated.check(true),
+            Err(Error::UnsupportedVersion(5))
+        ));
+    }
 }
@@ -35,4 +35,5 @@ impl AnkiRequest for VersionRequest {
 
     const ACTION: &'static str = "version";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -21,9 +21,17 @@
 * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 * SOFTWARE.
 */
-use std::marker::PhantomData;
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
 
-use crate::{AnkiRequest, AnkiRequestable, Result};
+use serde_json::Value;
+
+use crate::{error::AnkiError, AnkiRequest, AnkiRequestable, AsyncAnkiRequestable, Error, Result};
 
 pub struct MockAnkiClient<Request, F>
 where
@@ -34,9 +42,9 @@ where
     pub result: F,
 }
 
-impl<'a, Request, F> MockAnkiClient<Request, F>
+impl<Request, F> MockAnkiClient<Request, F>
 where
-    Request: AnkiRequest + Send + 'a,
+    Request: AnkiRequest + Send,
     F: FnOnce(Request) -> Result<Request::Response> + Send + Sync,
 {
     pub fn new_mock(result: F) -> Self {
@@ -47,10 +55,9 @@ where
     }
 }
 
-#[maybe_async::sync_impl]
-impl<'a, Request, F> AnkiRequestable<Request> for MockAnkiClient<Request, F>
+impl<Request, F> AnkiRequestable<Request> for MockAnkiClient<Request, F>
 where
-    Request: AnkiRequest + Send + Sync + 'a,
+    Request: AnkiRequest + Send + Sync,
     F: FnOnce(Request) -> Result<Request::Response> + Send + Sync + Copy,
 {
     fn request(&self, params: Request) -> Result<Request::Response> {
@@ -58,15 +65,224 @@ where
     }
 }
 
-#[maybe_async::async_impl]
+/// The asynchronous variant of the [`MockAnkiClient`].
+pub struct AsyncMockAnkiClient<Request, F>(pub MockAnkiClient<Request, F>)
+where
+    Request: AnkiRequest + Send,
+    F: FnOnce(Request) -> Result<Request::Response> + Send + Sync;
+
+impl<Request, F> AsyncMockAnkiClient<Request, F>
+where
+    Request: AnkiRequest + Send,
+    F: FnOnce(Request) -> Result<Request::Response> + Send + Sync,
+{
+    pub fn new_mock(result: F) -> Self {
+        Self(MockAnkiClient::new_mock(result))
+    }
+}
+
 #[async_trait::async_trait]
-impl<'a, Request, F> AnkiRequestable<Request> for MockAnkiClient<Request, F>
+impl<Request, F> AsyncAnkiRequestable<Request> for AsyncMockAnkiClient<Request, F>
 where
-    Request: AnkiRequest + Send + Sync + 'a,
+    Request: AnkiRequest + Send + Sync,
     F: FnOnce(Request) -> Result<Request::Response> + Send + Sync + Copy,
 {
     async fn request(&self, params: Request) -> Result<Request::Response> {
-        (self.result)(params)
+        (self.0.result)(params)
+    }
+}
+
+type MockHandler = Arc<Mutex<dyn FnMut(&Value) -> Result<Value> + Send>>;
+
+/// A request, which was received by the [`MockAnki`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    /// The action of the request.
+    pub action: &'static str,
+    /// The serialized parameters of the request.
+    pub params: Value,
+}
+
+#[derive(Default)]
+struct MockState {
+    handlers: HashMap<&'static str, MockHandler>,
+    calls: Vec<MockCall>,
+    latency: Option<Duration>,
+}
+
+/// A mock, which accepts every request type and routes it by its [`AnkiRequest::ACTION`] to the
+/// registered handler. Every call is recorded, so tests can assert on them afterwards.
+///
+/// Clones share the handlers and the recorded calls, so a clone can be moved into the code under
+/// test while the original is used for the assertions.
+///
+/// ```
+/// use anki_bridge::mock::MockAnki;
+/// use anki_bridge::prelude::*;
+/// use serde_json::json;
+///
+/// let anki = MockAnki::new();
+/// anki.respond::<DeckNamesRequest>(json!(["Default", "Japanese"]));
+/// anki.fail::<GetDeckStatsRequest>("collection is not available");
+///
+/// let decks = anki.request(DeckNamesRequest {}).unwrap();
+/// assert_eq!(decks, vec!["Default", "Japanese"]);
+/// assert!(anki.request(GetDeckStatsRequest { decks }).is_err());
+/// assert_eq!(anki.calls().len(), 2);
+/// ```
+#[derive(Clone, Default)]
+pub struct MockAnki {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockAnki {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Registers a handler for the request type, which receives the serialized parameters and
+    /// returns the serialized response. It replaces any previously registered handler.
+    ///
+    /// The handler is called without holding the state of the mock, so it may send other
+    /// requests to the mock, but not requests of its own type.
+    pub fn on<Request, F>(&self, handler: F) -> &Self
+    where
+        Request: AnkiRequest,
+        F: FnMut(&Value) -> Result<Value> + Send + 'static,
+    {
+        self.state()
+            .handlers
+            .insert(Request::ACTION, Arc::new(Mutex::new(handler)));
+        self
+    }
+
+    /// Always responds to the request type with the given JSON.
+    pub fn respond<Request: AnkiRequest>(&self, response: Value) -> &Self {
+        self.on::<Request, _>(move |_| Ok(response.clone()))
+    }
+
+    /// Always fails the request type with the given error message, as if it was returned by Anki.
+    pub fn fail<Request: AnkiRequest>(&self, message: impl Into<String>) -> &Self {
+        let message = message.into();
+        self.on::<Request, _>(move |_| Err(AnkiError::new(Request::ACTION, message.clone()).into()))
+    }
+
+    /// Delays every response by the given duration.
+    ///
+    /// The [`AsyncMockAnki`] waits with `tokio::time::sleep` if `tokio` is enabled, e.g. by the
+    /// `reqwest_async` feature. Otherwise it blocks the current thread like the synchronous mock.
+    pub fn set_latency(&self, latency: Duration) -> &Self {
+        self.state().latency = Some(latency);
+        self
+    }
+
+    /// Returns all recorded calls in the order they were received.
+    #[must_use]
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// Returns the parameters of all recorded calls of the request type.
+    #[must_use]
+    pub fn calls_of<Request: AnkiRequest>(&self) -> Vec<Value> {
+        self.state()
+            .calls
+            .iter()
+            .filter(|call| call.action == Request::ACTION)
+            .map(|call| call.params.clone())
+            .collect()
+    }
+
+    /// Removes all recorded calls, but keeps the handlers.
+    pub fn clear_calls(&self) {
+        self.state().calls.clear();
+    }
+
+    fn latency(&self) -> Option<Duration> {
+        self.state().latency
+    }
+
+    fn handle<Request: AnkiRequest>(&self, params: &Request) -> Result<Request::Response> {
+        let params = serde_json::to_value(params).map_err(|source| Error::Serialize {
+            action: Request::ACTION,
+            source,
+        })?;
+        let handler = {
+            let mut state = self.state();
+            state.calls.push(MockCall {
+                action: Request::ACTION,
+                params: params.clone(),
+            });
+            state.handlers.get(Request::ACTION).cloned()
+        };
+        let Some(handler) = handler else {
+            return Err(AnkiError::new(Request::ACTION, "unsupported action").into());
+        };
+        let response = {
+            let mut handler = handler
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            handler(&params)?
+        };
+        if response.is_null() {
+            return Ok(Default::default());
+        }
+        serde_json::from_value(response.clone())
+            .map_err(|error| Error::serde(Request::ACTION, &response.to_string(), error))
+    }
+}
+
+impl Debug for MockAnki {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("MockAnki")
+            .field("actions", &state.handlers.keys().collect::<Vec<_>>())
+            .field("calls", &state.calls)
+            .field("latency", &state.latency)
+            .finish()
+    }
+}
+
+impl<Request: AnkiRequest> AnkiRequestable<Request> for MockAnki {
+    fn request(&self, params: Request) -> Result<Request::Response> {
+        if let Some(latency) = self.latency() {
+            std::thread::sleep(latency);
+        }
+        self.handle(&params)
+    }
+}
+
+/// The asynchronous variant of the [`MockAnki`], which shares the state with the mock it was
+/// created from.
+#[derive(Debug, Clone, Default)]
+pub struct AsyncMockAnki(pub MockAnki);
+
+impl From<MockAnki> for AsyncMockAnki {
+    fn from(mock: MockAnki) -> Self {
+        Self(mock)
+    }
+}
+
+#[async_trait::async_trait]
+impl<Request> AsyncAnkiRequestable<Request> for AsyncMockAnki
+where
+    Request: AnkiRequest + Send + Sync + 'static,
+{
+    async fn request(&self, params: Request) -> Result<Request::Response> {
+        if let Some(latency) = self.0.latency() {
+            #[cfg(feature = "tokio")]
+            tokio::time::sleep(latency).await;
+            #[cfg(not(feature = "tokio"))]
+            std::thread::sleep(latency);
+        }
+        self.0.handle(&params)
     }
 }
 
@@ -74,7 +290,14 @@ where
 mod tests {
     use serde::{Deserialize, Serialize};
 
-    use crate::card_actions::find_cards::FindCardsRequest;
+    use serde_json::json;
+
+    use crate::{
+        card_actions::find_cards::FindCardsRequest,
+        deck_actions::{deck_names::DeckNamesRequest, get_deck_stats::GetDeckStatsRequest},
+        error::AnkiErrorKind,
+        ids::CardId,
+    };
 
     use super::*;
 
@@ -95,7 +318,6 @@ mod tests {
         const VERSION: u8 = 6;
     }
 
-    #[cfg(any(feature = "reqwest_blocking", feature = "ureq_blocking"))]
     #[test]
     fn test_sync_mock_client() {
         let client = MockAnkiClient::<TestRequest, _>::new_mock(|params| {
@@ -109,10 +331,9 @@ mod tests {
         assert_eq!(String::from("HelloWorld"), response.unwrap().data);
     }
 
-    #[cfg(feature = "reqwest_async")]
     #[tokio::test]
     async fn test_async_mock_client() {
-        let client = MockAnkiClient::<TestRequest, _>::new_mock(|params| {
+        let client = AsyncMockAnkiClient::<TestRequest, _>::new_mock(|params| {
             Ok(TestResponse {
                 data: format!("{}World", params.data),
             })
@@ -125,26 +346,39 @@ mod tests {
         assert_eq!(String::from("HelloWorld"), response.unwrap().data);
     }
 
-    #[cfg(any(feature = "reqwest_blocking", feature = "ureq_blocking"))]
     #[test]
     fn test_sync_find_cards() {
         let client = MockAnkiClient::<FindCardsRequest, _>::new_mock(|params| {
-            Ok(vec![123, 456, 789, params.query.len()])
+            Ok(vec![
+                CardId(123),
+                CardId(456),
+                CardId(789),
+                params.query.len().try_into().unwrap(),
+            ])
         });
         let response = client.request(FindCardsRequest {
             query: "Card Deck Name".to_string(),
         });
         assert_eq!(
-            vec![123, 456, 789, "Card Deck Name".len()],
+            vec![
+                CardId(123),
+                CardId(456),
+                CardId(789),
+                "Card Deck Name".len().try_into().unwrap()
+            ],
             response.unwrap()
         );
     }
 
-    #[cfg(feature = "reqwest_async")]
     #[tokio::test]
     async fn test_async_find_cards() {
-        let client = MockAnkiClient::<FindCardsRequest, _>::new_mock(|params| {
-            Ok(vec![123, 456, 789, params.query.len()])
+        let client = AsyncMockAnkiClient::<FindCardsRequest, _>::new_mock(|params| {
+            Ok(vec![
+                CardId(123),
+                CardId(456),
+                CardId(789),
+                params.query.len().try_into().unwrap(),
+            ])
         });
         let response = client
             .request(FindCardsRequest {
@@ -152,8 +386,107 @@ mod tests {
             })
             .await;
         assert_eq!(
-            vec![123, 456, 789, "Card Deck Name".len()],
+            vec![
+                CardId(123),
+                CardId(456),
+                CardId(789),
+                "Card Deck Name".len().try_into().unwrap()
+            ],
             response.unwrap()
         );
     }
+
+    #[test]
+    fn test_mock_anki_routes_by_action() {
+        let anki = MockAnki::new();
+        anki.respond::<DeckNamesRequest>(json!(["Default"]))
+            .on::<FindCardsRequest, _>(|params| {
+                Ok(json!([params["query"].as_str().unwrap().len()]))
+            })
+            .fail::<GetDeckStatsRequest>("deck was not found: Default");
+
+        assert_eq!(anki.request(DeckNamesRequest {}).unwrap(), vec!["Default"]);
+        let cards = anki
+            .request(FindCardsRequest {
+                query: "deck:Default".to_string(),
+            })
+            .unwrap();
+        assert_eq!(cards, vec![CardId(12)]);
+        let error = anki
+            .request(GetDeckStatsRequest {
+                decks: vec!["Default".to_string()],
+            })
+            .unwrap_err();
+        assert_eq!(error.anki_kind(), Some(AnkiErrorKind::DeckNotFound));
+        let error = anki.request(TestRequest::default()).unwrap_err();
+        assert_eq!(error.anki_kind(), Some(AnkiErrorKind::UnsupportedAction));
+
+        assert_eq!(anki.calls().len(), 4);
+        assert_eq!(
+            anki.calls_of::<FindCardsRequest>(),
+            vec![json!({ "query": "deck:Default" })]
+        );
+    }
+
+    #[test]
+    fn test_mock_anki_serialization_error() {
+        /// A request, whose map keys can't be serialized to JSON.
+        #[derive(Debug, Serialize)]
+        struct InvalidRequest(HashMap<(u8, u8), u8>);
+
+        impl AnkiRequest for InvalidRequest {
+            type Response = ();
+
+            const ACTION: &'static str = "invalid";
+            const VERSION: u8 = 6;
+        }
+
+        let anki = MockAnki::new();
+        let error = anki
+            .request(InvalidRequest(HashMap::from([((1, 2), 3)])))
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Serialize {
+                action: "invalid",
+                ..
+            }
+        ));
+        assert!(anki.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_async_mock_anki_shares_calls() {
+        let anki = MockAnki::new();
+        anki.respond::<TestRequest>(json!({ "data": "World" }));
+        let client = AsyncMockAnki::from(anki.clone());
+        let response = client.request(TestRequest::default()).await.unwrap();
+        assert_eq!(response.data, "World");
+        assert_eq!(anki.calls_of::<TestRequest>().len(), 1);
+    }
+
+    #[test]
+    fn test_mock_anki_handler_can_send_requests() {
+        let anki = MockAnki::new();
+        anki.respond::<DeckNamesRequest>(json!(["Default"]));
+        let inner = anki.clone();
+        anki.on::<FindCardsRequest, _>(move |_| {
+            let decks = inner.request(DeckNamesRequest {})?;
+            Ok(json!([decks.len()]))
+        });
+        let cards = anki
+            .request(FindCardsRequest {
+                query: String::new(),
+            })
+            .unwrap();
+        assert_eq!(cards, vec![CardId(1)]);
+        assert_eq!(anki.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_mock_anki_null_response() {
+        let anki = MockAnki::new();
+        anki.respond::<DeckNamesRequest>(Value::Null);
+        assert!(anki.request(DeckNamesRequest {}).unwrap().is_empty());
+    }
 }
@@ -22,13 +22,14 @@
 * SOFTWARE.
 */
 
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use serde::Serialize;
-
-use crate::AnkiRequest;
+use crate::{ids::ModelId, model_actions::model_definition::CardTemplate, AnkiRequest};
 
 /// Parameters for the "createModel" action in `AnkiConnect`.
+///
+/// It can be built from a validated [`ModelDefinition`](super::model_definition::ModelDefinition).
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateModelRequest {
@@ -36,11 +37,64 @@ pub struct CreateModelRequest {
     pub in_order_fields: Vec<String>,
     pub css: String,
     pub is_cloze: bool,
-    pub card_templates: Vec<HashMap<String, String>>,
+    pub card_templates: Vec<CardTemplate>,
 }
-// TODO: parse response
+
+/// The model, which was created.
+#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CreateModelResponse {
+    /// The ID of the new model.
+    pub id: ModelId,
+    /// The name of the new model.
+    pub name: String,
+    /// The CSS of the new model.
+    pub css: String,
+    /// The fields of the new model in order.
+    #[serde(rename = "flds")]
+    pub fields: Vec<CreateModelField>,
+    /// The templates of the new model in order.
+    #[serde(rename = "tmpls")]
+    pub templates: Vec<CreateModelTemplate>,
+    /// The other properties of the model, like its type and required fields.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A field of the model, which was created.
+#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CreateModelField {
+    /// The name of the field.
+    pub name: String,
+    /// The position of the field.
+    pub ord: usize,
+    /// The other properties of the field, like its font.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A template of the model, which was created.
+#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CreateModelTemplate {
+    /// The name of the template.
+    pub name: String,
+    /// The position of the template.
+    pub ord: usize,
+    /// The front side of the template.
+    #[serde(rename = "qfmt")]
+    pub front: String,
+    /// The back side of the template.
+    #[serde(rename = "afmt")]
+    pub back: String,
+    /// The other properties of the template, like its browser appearance.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
 impl AnkiRequest for CreateModelRequest {
-    type Response = ();
+    type Response = CreateModelResponse;
 
     const ACTION: &'static str = "createModel";
     const VERSION: u8 = 6;
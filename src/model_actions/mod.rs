@@ -26,6 +26,8 @@
 pub mod create_model;
 /// Find and replace string in existing model by model name. Customise to replace in front, back or css by setting to true/false.
 pub mod find_and_replace_in_models;
+/// The validated definition of a model, which can be created with "createModel".
+pub mod model_definition;
 /// Creates a new field within a given model.
 /// Optionally, the `index` value can be provided, which works exactly the same as the index in `modelFieldReposition`. By default, the field is added to the end of the field list.
 pub mod model_field_add;
@@ -51,6 +53,8 @@ pub mod model_field_set_font;
 pub mod model_field_set_font_size;
 /// Returns an object indicating the fields on the question and answer side of each card template for the given model name. The question side is given first in each array.
 pub mod model_fields_on_templates;
+/// Migrates a model in Anki to a model definition with the minimal ordered set of requests.
+pub mod model_migration;
 /// Gets the complete list of model names for the current user.
 pub mod model_names;
 /// Gets the complete list of model names and their corresponding IDs for the current user.
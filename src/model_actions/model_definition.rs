@@ -0,0 +1,435 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::model_actions::{
+    create_model::CreateModelRequest, model_field_set_description::ModelFieldSetDescriptionRequest,
+    model_field_set_font::ModelFieldSetFontRequest,
+    model_field_set_font_size::ModelFieldSetFontSizeRequest,
+};
+
+/// The special fields, which can be used in every template.
+const SPECIAL_FIELDS: &[&str] = &[
+    "Card",
+    "CardFlag",
+    "CardID",
+    "Deck",
+    "FrontSide",
+    "Subdeck",
+    "Tags",
+    "Type",
+];
+
+/// Errors which can occur while building a [`ModelDefinition`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ModelDefinitionError {
+    /// The model has no fields.
+    #[error("the model has no fields")]
+    NoFields,
+
+    /// The model has no templates.
+    #[error("the model has no templates")]
+    NoTemplates,
+
+    /// Two fields have the same name.
+    #[error("the field `{0}` is defined twice")]
+    DuplicateField(String),
+
+    /// Two templates have the same name.
+    #[error("the template `{0}` is defined twice")]
+    DuplicateTemplate(String),
+
+    /// A template references a field, which isn't defined.
+    #[error("the template `{template}` references the unknown field `{field}`")]
+    UnknownField { template: String, field: String },
+
+    /// A template of a cloze model doesn't contain a `{{cloze:...}}` on its front.
+    #[error("the template `{0}` of the cloze model doesn't contain a `{{{{cloze:...}}}}`")]
+    MissingCloze(String),
+}
+
+/// A card template of a model.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CardTemplate {
+    /// The name of the template.
+    pub name: String,
+    /// The front side, which is called question format in Anki.
+    pub front: String,
+    /// The back side, which is called answer format in Anki.
+    pub back: String,
+}
+
+impl CardTemplate {
+    #[must_use]
+    pub fn new(name: impl Into<String>, front: impl Into<String>, back: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            front: front.into(),
+            back: back.into(),
+        }
+    }
+}
+
+/// A field of a model with its optional editor settings.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ModelField {
+    /// The name of the field.
+    pub name: String,
+    /// The text, which is shown in the editor while the field is empty.
+    pub description: Option<String>,
+    /// The font of the field in the editor.
+    pub font: Option<String>,
+    /// The font size of the field in the editor.
+    pub font_size: Option<usize>,
+}
+
+impl ModelField {
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the description, which is shown in the editor while the field is empty.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the font of the field in the editor.
+    #[must_use]
+    pub fn font(mut self, font: impl Into<String>) -> Self {
+        self.font = Some(font.into());
+        self
+    }
+
+    /// Sets the font size of the field in the editor.
+    #[must_use]
+    pub const fn font_size(mut self, font_size: usize) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+}
+
+/// The validated definition of a model, which is called note type in Anki.
+///
+/// It is built with [`ModelDefinition::builder`] and can be created with
+/// [`ModelDefinition::create_request`]. The field settings aren't supported by "createModel", so
+/// they are sent afterwards with the requests of [`ModelDefinition::description_requests`],
+/// [`ModelDefinition::font_requests`] and [`ModelDefinition::font_size_requests`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ModelDefinition {
+    /// The name of the model.
+    pub name: String,
+    /// The fields of the model in order.
+    pub fields: Vec<ModelField>,
+    /// The card templates of the model in order.
+    pub templates: Vec<CardTemplate>,
+    /// The CSS, which is shared by all templates.
+    pub css: String,
+    /// Whether the model is a cloze model.
+    pub is_cloze: bool,
+}
+
+impl ModelDefinition {
+    #[must_use]
+    pub fn builder(name: impl Into<String>) -> ModelDefinitionBuilder {
+        ModelDefinitionBuilder {
+            definition: Self {
+                name: name.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Checks that the names are unique, that the templates reference only existing fields and
+    /// that the templates of a cloze model contain a cloze deletion.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem, which was found.
+    pub fn validate(&self) -> Result<(), ModelDefinitionError> {
+        if self.fields.is_empty() {
+            return Err(ModelDefinitionError::NoFields);
+        }
+        if self.templates.is_empty() {
+            return Err(ModelDefinitionError::NoTemplates);
+        }
+        let mut fields = HashSet::new();
+        for field in &self.fields {
+            if !fields.insert(field.name.as_str()) {
+                return Err(ModelDefinitionError::DuplicateField(field.name.clone()));
+            }
+        }
+        let mut templates = HashSet::new();
+        for template in &self.templates {
+            if !templates.insert(template.name.as_str()) {
+                return Err(ModelDefinitionError::DuplicateTemplate(
+                    template.name.clone(),
+                ));
+            }
+            let references =
+                referenced_fields(&template.front).chain(referenced_fields(&template.back));
+            for reference in references {
+                if !fields.contains(reference.field) && !SPECIAL_FIELDS.contains(&reference.field) {
+                    return Err(ModelDefinitionError::UnknownField {
+                        template: template.name.clone(),
+                        field: reference.field.to_string(),
+                    });
+                }
+            }
+            if self.is_cloze && !referenced_fields(&template.front).any(|r| r.is_cloze) {
+                return Err(ModelDefinitionError::MissingCloze(template.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the names of the fields in order.
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|field| field.name.as_str())
+    }
+
+    /// Returns the request, which creates the model.
+    #[must_use]
+    pub fn create_request(&self) -> CreateModelRequest {
+        CreateModelRequest {
+            model_name: self.name.clone(),
+            in_order_fields: self.field_names().map(str::to_string).collect(),
+            css: self.css.clone(),
+            is_cloze: self.is_cloze,
+            card_templates: self.templates.clone(),
+        }
+    }
+
+    /// Returns the requests, which set the descriptions of the fields.
+    #[must_use]
+    pub fn description_requests(&self) -> Vec<ModelFieldSetDescriptionRequest> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                Some(ModelFieldSetDescriptionRequest {
+                    model_name: self.name.clone(),
+                    field_name: field.name.clone(),
+                    description: field.description.clone()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the requests, which set the fonts of the fields.
+    #[must_use]
+    pub fn font_requests(&self) -> Vec<ModelFieldSetFontRequest> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                Some(ModelFieldSetFontRequest {
+                    model_name: self.name.clone(),
+                    field_name: field.name.clone(),
+                    font: field.font.clone()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the requests, which set the font sizes of the fields.
+    #[must_use]
+    pub fn font_size_requests(&self) -> Vec<ModelFieldSetFontSizeRequest> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                Some(ModelFieldSetFontSizeRequest {
+                    model_name: self.name.clone(),
+                    field_name: field.name.clone(),
+                    font_size: field.font_size?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl From<&ModelDefinition> for CreateModelRequest {
+    fn from(definition: &ModelDefinition) -> Self {
+        definition.create_request()
+    }
+}
+
+/// Builds a validated [`ModelDefinition`].
+#[derive(Debug, Clone)]
+pub struct ModelDefinitionBuilder {
+    definition: ModelDefinition,
+}
+
+impl ModelDefinitionBuilder {
+    /// Adds a field without editor settings.
+    #[must_use]
+    pub fn field(self, name: impl Into<String>) -> Self {
+        self.field_with(ModelField::new(name))
+    }
+
+    /// Adds a field with editor settings.
+    #[must_use]
+    pub fn field_with(mut self, field: ModelField) -> Self {
+        self.definition.fields.push(field);
+        self
+    }
+
+    /// Adds a card template.
+    #[must_use]
+    pub fn template(
+        mut self,
+        name: impl Into<String>,
+        front: impl Into<String>,
+        back: impl Into<String>,
+    ) -> Self {
+        self.definition
+            .templates
+            .push(CardTemplate::new(name, front, back));
+        self
+    }
+
+    /// Sets the CSS, which is shared by all templates.
+    #[must_use]
+    pub fn css(mut self, css: impl Into<String>) -> Self {
+        self.definition.css = css.into();
+        self
+    }
+
+    /// Sets whether the model is a cloze model, defaults to `false`.
+    #[must_use]
+    pub const fn cloze(mut self, is_cloze: bool) -> Self {
+        self.definition.is_cloze = is_cloze;
+        self
+    }
+
+    /// Validates and returns the definition.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found by [`ModelDefinition::validate`].
+    pub fn build(self) -> Result<ModelDefinition, ModelDefinitionError> {
+        self.definition.validate()?;
+        Ok(self.definition)
+    }
+}
+
+/// A field, which is referenced by a `{{...}}` tag of a template.
+struct FieldReference<'a> {
+    field: &'a str,
+    is_cloze: bool,
+}
+
+/// Returns the fields, which are referenced by the tags of a template.
+///
+/// Conditional tags like `{{#Field}}` and filters like `{{text:Field}}` reference the field after
+/// the markers and filters.
+fn referenced_fields(template: &str) -> impl Iterator<Item = FieldReference<'_>> {
+    template.split("{{").skip(1).filter_map(|tag| {
+        let tag = tag.split_once("}}")?.0.trim();
+        let tag = tag.trim_start_matches(['#', '^', '/']).trim();
+        let (filters, field) = tag.rsplit_once(':').unwrap_or(("", tag));
+        let field = field.trim();
+        (!field.is_empty()).then(|| FieldReference {
+            field,
+            is_cloze: filters.split(':').any(|filter| filter.trim() == "cloze"),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnkiRequest;
+
+    #[test]
+    fn test_build_validates_templates() {
+        let definition = ModelDefinition::builder("Japanese")
+            .field("Expression")
+            .field_with(ModelField::new("Meaning").description("English meaning"))
+            .template(
+                "Recognition",
+                "{{Expression}}{{#Meaning}}?{{/Meaning}}",
+                "{{FrontSide}}<hr id=answer>{{text:Meaning}}",
+            )
+            .build()
+            .unwrap();
+        assert_eq!(definition.description_requests().len(), 1);
+        assert!(definition.font_requests().is_empty());
+
+        let error = ModelDefinition::builder("Japanese")
+            .field("Expression")
+            .template("Recognition", "{{Expression}}", "{{Reading}}")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ModelDefinitionError::UnknownField {
+                template: "Recognition".to_string(),
+                field: "Reading".to_string(),
+            }
+        );
+
+        let error = ModelDefinition::builder("My Cloze")
+            .field("Text")
+            .template("Cloze", "{{Text}}", "{{Text}}")
+            .cloze(true)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ModelDefinitionError::MissingCloze("Cloze".to_string())
+        );
+    }
+
+    #[test]
+    fn test_font_size_requests() {
+        let definition = ModelDefinition::builder("Japanese")
+            .field_with(ModelField::new("Expression").font_size(28))
+            .field("Meaning")
+            .template("Recognition", "{{Expression}}", "{{Meaning}}")
+            .build()
+            .unwrap();
+        let requests = definition.font_size_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            serde_json::json!({
+                "action": "modelFieldSetFontSize",
+                "version": 6,
+                "params": {
+                    "modelName": "Japanese",
+                    "fieldName": "Expression",
+                    "fontSize": 28,
+                },
+            }),
+            requests[0].to_json()
+        );
+    }
+}
@@ -38,4 +38,5 @@ impl AnkiRequest for ModelFieldDescriptionsRequest {
 
     const ACTION: &'static str = "modelFieldDescriptions";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -46,4 +46,5 @@ impl AnkiRequest for ModelFieldFontsRequest {
 
     const ACTION: &'static str = "modelFieldFonts";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
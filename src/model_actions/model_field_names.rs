@@ -38,4 +38,5 @@ impl AnkiRequest for ModelFieldNamesRequest {
 
     const ACTION: &'static str = "modelFieldNames";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
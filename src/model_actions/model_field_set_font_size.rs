@@ -32,7 +32,7 @@ use crate::AnkiRequest;
 pub struct ModelFieldSetFontSizeRequest {
     pub model_name: String,
     pub field_name: String,
-    pub font_size: String,
+    pub font_size: usize,
 }
 
 impl AnkiRequest for ModelFieldSetFontSizeRequest {
@@ -40,4 +40,5 @@ impl AnkiRequest for ModelFieldsOnTemplatesRequest {
 
     const ACTION: &'static str = "modelFieldsOnTemplates";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
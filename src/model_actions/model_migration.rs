@@ -0,0 +1,809 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+use thiserror::Error;
+
+use crate::{
+    model_actions::{
+        create_model::CreateModelRequest,
+        model_definition::{CardTemplate, ModelDefinition},
+        model_field_add::ModelFieldAddRequest,
+        model_field_names::ModelFieldNamesRequest,
+        model_field_remove::ModelFieldRemoveRequest,
+        model_field_rename::ModelFieldRenameRequest,
+        model_field_reposition::ModelFieldRepositionRequest,
+        model_names::ModelNamesRequest,
+        model_styling::ModelStylingRequest,
+        model_template_add::ModelTemplateAddRequest,
+        model_template_remove::ModelTemplateRemoveRequest,
+        model_template_rename::ModelTemplateRenameRequest,
+        model_templates::ModelTemplatesRequest,
+        update_model_styling::{UpdateModelStylingModelRequest, UpdateModelStylingRequest},
+        update_model_templates::{UpdateModelTemplatesModelRequest, UpdateModelTemplatesRequest},
+    },
+    send, send_async, AnkiRequestable, AsyncAnkiRequestable, Error, Result,
+};
+
+/// Errors which can occur while planning a [`ModelMigration`].
+#[derive(Debug, Error)]
+pub enum ModelMigrationError {
+    /// The migration would remove fields or templates, which deletes their content or cards,
+    /// but removals weren't allowed with [`ModelMigration::allow_removals`].
+    #[error("the migration would remove the fields {fields:?} and the templates {templates:?}")]
+    RemovalNotAllowed {
+        /// The fields, which would be removed.
+        fields: Vec<String>,
+        /// The templates, which would be removed.
+        templates: Vec<String>,
+    },
+
+    /// The current model couldn't be read.
+    #[error(transparent)]
+    Request(#[from] Error),
+}
+
+/// The current state of a model, as read from Anki.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ModelState {
+    /// The names of the fields in order.
+    pub fields: Vec<String>,
+    /// The templates keyed by their name.
+    pub templates: HashMap<String, CardTemplate>,
+    /// The CSS of the model.
+    pub css: String,
+}
+
+/// A single change of a model, which is sent as one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelOperation {
+    CreateModel(CreateModelRequest),
+    RenameField(ModelFieldRenameRequest),
+    AddField(ModelFieldAddRequest),
+    RemoveField(ModelFieldRemoveRequest),
+    RepositionField(ModelFieldRepositionRequest),
+    RenameTemplate(ModelTemplateRenameRequest),
+    AddTemplate(ModelTemplateAddRequest),
+    UpdateTemplates(UpdateModelTemplatesRequest),
+    RemoveTemplate(ModelTemplateRemoveRequest),
+    UpdateStyling(UpdateModelStylingRequest),
+}
+
+impl Display for ModelOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateModel(request) => write!(
+                f,
+                "create model `{}` with the fields {:?}",
+                request.model_name, request.in_order_fields
+            ),
+            Self::RenameField(request) => write!(
+                f,
+                "rename field `{}` to `{}`",
+                request.old_field_name, request.new_field_name
+            ),
+            Self::AddField(request) => match request.index {
+                Some(index) => write!(f, "add field `{}` at position {index}", request.field_name),
+                None => write!(f, "add field `{}`", request.field_name),
+            },
+            Self::RemoveField(request) => write!(f, "remove field `{}`", request.field_name),
+            Self::RepositionField(request) => write!(
+                f,
+                "move field `{}` to position {}",
+                request.field_name, request.index
+            ),
+            Self::RenameTemplate(request) => write!(
+                f,
+                "rename template `{}` to `{}`",
+                request.old_template_name, request.new_template_name
+            ),
+            Self::AddTemplate(request) => write!(
+                f,
+                "add template `{}`",
+                request.template.get("Name").map_or("", String::as_str)
+            ),
+            Self::UpdateTemplates(request) => {
+                let mut names = request.model.templates.keys().collect::<Vec<_>>();
+                names.sort();
+                write!(f, "update the templates {names:?}")
+            }
+            Self::RemoveTemplate(request) => {
+                write!(f, "remove template `{}`", request.template_name)
+            }
+            Self::UpdateStyling(_) => write!(f, "update the styling"),
+        }
+    }
+}
+
+/// The ordered operations, which migrate a model to its definition.
+///
+/// Its [`Display`] lists one operation per line, which can be shown as a dry run.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ModelMigrationPlan {
+    /// The name of the model.
+    pub model: String,
+    /// The operations in the order in which they have to be applied.
+    pub operations: Vec<ModelOperation>,
+}
+
+impl ModelMigrationPlan {
+    /// Returns whether the model already matches its definition.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Applies the operations in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first request, which failed. The former operations stay applied.
+    pub fn apply<C: ModelMigrationClient>(&self, client: &C) -> Result<()> {
+        for operation in &self.operations {
+            match operation.clone() {
+                ModelOperation::CreateModel(request) => send(client, request).map(drop)?,
+                ModelOperation::RenameField(request) => send(client, request)?,
+                ModelOperation::AddField(request) => send(client, request)?,
+                ModelOperation::RemoveField(request) => send(client, request)?,
+                ModelOperation::RepositionField(request) => send(client, request)?,
+                ModelOperation::RenameTemplate(request) => send(client, request)?,
+                ModelOperation::AddTemplate(request) => send(client, request)?,
+                ModelOperation::UpdateTemplates(request) => send(client, request)?,
+                ModelOperation::RemoveTemplate(request) => send(client, request)?,
+                ModelOperation::UpdateStyling(request) => send(client, request)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the operations in order asynchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first request, which failed. The former operations stay applied.
+    pub async fn apply_async<C: AsyncModelMigrationClient>(&self, client: &C) -> Result<()> {
+        for operation in &self.operations {
+            match operation.clone() {
+                ModelOperation::CreateModel(request) => {
+                    send_async(client, request).await.map(drop)?;
+                }
+                ModelOperation::RenameField(request) => send_async(client, request).await?,
+                ModelOperation::AddField(request) => send_async(client, request).await?,
+                ModelOperation::RemoveField(request) => send_async(client, request).await?,
+                ModelOperation::RepositionField(request) => send_async(client, request).await?,
+                ModelOperation::RenameTemplate(request) => send_async(client, request).await?,
+                ModelOperation::AddTemplate(request) => send_async(client, request).await?,
+                ModelOperation::UpdateTemplates(request) => send_async(client, request).await?,
+                ModelOperation::RemoveTemplate(request) => send_async(client, request).await?,
+                ModelOperation::UpdateStyling(request) => send_async(client, request).await?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for ModelMigrationPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.operations.is_empty() {
+            return writeln!(f, "model `{}` is up to date", self.model);
+        }
+        writeln!(f, "migrate model `{}`:", self.model)?;
+        for operation in &self.operations {
+            writeln!(f, "  {operation}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Migrates a model in Anki to a [`ModelDefinition`], e.g. one which is versioned in git.
+///
+/// Fields and templates are matched by name, so renames have to be declared with
+/// [`ModelMigration::rename_field`] and [`ModelMigration::rename_template`], otherwise they are
+/// planned as a removal and an addition. The order of the templates isn't migrated, because
+/// "modelTemplates" doesn't report it, and neither are the editor settings of the fields.
+///
+/// ```no_run
+/// # #[cfg(feature = "ureq_blocking")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use anki_bridge::{
+///     model_actions::{model_definition::ModelDefinition, model_migration::ModelMigration},
+///     prelude::*,
+/// };
+///
+/// let definition = ModelDefinition::builder("Japanese")
+///     .field("Expression")
+///     .field("Meaning")
+///     .template("Recognition", "{{Expression}}", "{{FrontSide}}<hr>{{Meaning}}")
+///     .build()?;
+/// let client = UreqClient::default();
+/// let plan = ModelMigration::new(definition)
+///     .rename_field("English", "Meaning")
+///     .plan(&client)?;
+/// print!("{plan}");
+/// plan.apply(&client)?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "ureq_blocking"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModelMigration {
+    definition: ModelDefinition,
+    field_renames: Vec<(String, String)>,
+    template_renames: Vec<(String, String)>,
+    allow_removals: bool,
+}
+
+impl ModelMigration {
+    #[must_use]
+    pub const fn new(definition: ModelDefinition) -> Self {
+        Self {
+            definition,
+            field_renames: Vec::new(),
+            template_renames: Vec::new(),
+            allow_removals: false,
+        }
+    }
+
+    /// Declares that the field `old` was renamed to `new`, so its content is kept.
+    #[must_use]
+    pub fn rename_field(mut self, old: impl Into<String>, new: impl Into<String>) -> Self {
+        self.field_renames.push((old.into(), new.into()));
+        self
+    }
+
+    /// Declares that the template `old` was renamed to `new`, so its cards are kept.
+    #[must_use]
+    pub fn rename_template(mut self, old: impl Into<String>, new: impl Into<String>) -> Self {
+        self.template_renames.push((old.into(), new.into()));
+        self
+    }
+
+    /// Allows removing fields and templates, which deletes their content and cards, defaults to
+    /// `false`.
+    #[must_use]
+    pub const fn allow_removals(mut self, allow_removals: bool) -> Self {
+        self.allow_removals = allow_removals;
+        self
+    }
+
+    /// Reads the current model from Anki and plans the migration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the model couldn't be read or if the migration would remove fields or
+    /// templates without [`ModelMigration::allow_removals`].
+    pub fn plan<C: ModelMigrationClient>(
+        &self,
+        client: &C,
+    ) -> std::result::Result<ModelMigrationPlan, ModelMigrationError> {
+        let name = &self.definition.name;
+        if !send(client, ModelNamesRequest)?.contains(name) {
+            return self.plan_for(None);
+        }
+        let fields = send(
+            client,
+            ModelFieldNamesRequest {
+                model_name: name.clone(),
+            },
+        )?;
+        let templates = send(
+            client,
+            ModelTemplatesRequest {
+                model_name: name.clone(),
+            },
+        )?;
+        let styling = send(
+            client,
+            ModelStylingRequest {
+                model_name: name.clone(),
+            },
+        )?;
+        self.plan_for(Some(&state(fields, templates, styling.css)))
+    }
+
+    /// Reads the current model from Anki asynchronously and plans the migration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the model couldn't be read or if the migration would remove fields or
+    /// templates without [`ModelMigration::allow_removals`].
+    pub async fn plan_async<C: AsyncModelMigrationClient>(
+        &self,
+        client: &C,
+    ) -> std::result::Result<ModelMigrationPlan, ModelMigrationError> {
+        let name = &self.definition.name;
+        if !send_async(client, ModelNamesRequest).await?.contains(name) {
+            return self.plan_for(None);
+        }
+        let fields = send_async(
+            client,
+            ModelFieldNamesRequest {
+                model_name: name.clone(),
+            },
+        )
+        .await?;
+        let templates = send_async(
+            client,
+            ModelTemplatesRequest {
+                model_name: name.clone(),
+            },
+        )
+        .await?;
+        let styling = send_async(
+            client,
+            ModelStylingRequest {
+                model_name: name.clone(),
+            },
+        )
+        .await?;
+        self.plan_for(Some(&state(fields, templates, styling.css)))
+    }
+
+    /// Plans the migration from the given state, or the creation of the model if it is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the migration would remove fields or templates without
+    /// [`ModelMigration::allow_removals`].
+    pub fn plan_for(
+        &self,
+        current: Option<&ModelState>,
+    ) -> std::result::Result<ModelMigrationPlan, ModelMigrationError> {
+        let definition = &self.definition;
+        let model_name = definition.name.clone();
+        let mut plan = ModelMigrationPlan {
+            model: model_name.clone(),
+            operations: Vec::new(),
+        };
+        let Some(current) = current else {
+            plan.operations
+                .push(ModelOperation::CreateModel(definition.create_request()));
+            return Ok(plan);
+        };
+        let operations = &mut plan.operations;
+
+        // Fields: renames first to keep their content. The removals and moves come before the
+        // additions, so every field is added at its final position, unless no field is kept,
+        // because a model needs at least one field.
+        let desired_fields = definition.field_names().collect::<Vec<_>>();
+        let mut fields = current.fields.clone();
+        for (old, new) in &self.field_renames {
+            let Some(index) = fields.iter().position(|field| field == old) else {
+                continue;
+            };
+            if fields.contains(new) {
+                continue;
+            }
+            operations.push(ModelOperation::RenameField(ModelFieldRenameRequest {
+                model_name: model_name.clone(),
+                old_field_name: old.clone(),
+                new_field_name: new.clone(),
+            }));
+            fields[index].clone_from(new);
+        }
+        let (kept_fields, removed_fields): (Vec<_>, Vec<_>) = fields
+            .into_iter()
+            .partition(|field| desired_fields.contains(&field.as_str()));
+        let removals = removed_fields
+            .iter()
+            .map(|field| {
+                ModelOperation::RemoveField(ModelFieldRemoveRequest {
+                    model_name: model_name.clone(),
+                    field_name: field.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let additions = desired_fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| !kept_fields.iter().any(|kept| kept == *field))
+            .map(|(index, field)| {
+                ModelOperation::AddField(ModelFieldAddRequest {
+                    model_name: model_name.clone(),
+                    field_name: (*field).to_string(),
+                    index: Some(index),
+                })
+            })
+            .collect::<Vec<_>>();
+        if kept_fields.is_empty() {
+            operations.extend(additions);
+            operations.extend(removals);
+        } else {
+            operations.extend(removals);
+            let kept_order = desired_fields
+                .iter()
+                .copied()
+                .filter(|field| kept_fields.iter().any(|kept| kept == field))
+                .collect::<Vec<_>>();
+            for (field, index) in reposition(&kept_fields, &kept_order) {
+                operations.push(ModelOperation::RepositionField(
+                    ModelFieldRepositionRequest {
+                        model_name: model_name.clone(),
+                        field_name: field,
+                        index,
+                    },
+                ));
+            }
+            operations.extend(additions);
+        }
+
+        // Templates: additions before removals, because a model needs at least one template.
+        let mut templates = current.templates.clone();
+        for (old, new) in &self.template_renames {
+            if templates.contains_key(new) {
+                continue;
+            }
+            let Some(mut template) = templates.remove(old) else {
+                continue;
+            };
+            operations.push(ModelOperation::RenameTemplate(ModelTemplateRenameRequest {
+                model_name: model_name.clone(),
+                old_template_name: old.clone(),
+                new_template_name: new.clone(),
+            }));
+            template.name.clone_from(new);
+            templates.insert(new.clone(), template);
+        }
+        let mut updated = HashMap::new();
+        for template in &definition.templates {
+            match templates.get(&template.name) {
+                None => operations.push(ModelOperation::AddTemplate(ModelTemplateAddRequest {
+                    model_name: model_name.clone(),
+                    template: HashMap::from([
+                        ("Name".to_string(), template.name.clone()),
+                        ("Front".to_string(), template.front.clone()),
+                        ("Back".to_string(), template.back.clone()),
+                    ]),
+                })),
+                Some(existing) if existing != template => {
+                    updated.insert(
+                        template.name.clone(),
+                        HashMap::from([
+                            ("Front".to_string(), template.front.clone()),
+                            ("Back".to_string(), template.back.clone()),
+                        ]),
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        if !updated.is_empty() {
+            operations.push(ModelOperation::UpdateTemplates(
+                UpdateModelTemplatesRequest {
+                    model: UpdateModelTemplatesModelRequest {
+                        name: model_name.clone(),
+                        templates: updated,
+                    },
+                },
+            ));
+        }
+        let mut removed_templates = templates
+            .keys()
+            .filter(|name| {
+                !definition
+                    .templates
+                    .iter()
+                    .any(|template| template.name == **name)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        removed_templates.sort();
+        for template in &removed_templates {
+            operations.push(ModelOperation::RemoveTemplate(ModelTemplateRemoveRequest {
+                model_name: model_name.clone(),
+                template_name: template.clone(),
+            }));
+        }
+
+        if current.css != definition.css {
+            operations.push(ModelOperation::UpdateStyling(UpdateModelStylingRequest {
+                model: UpdateModelStylingModelRequest {
+                    name: model_name,
+                    css: definition.css.clone(),
+                },
+            }));
+        }
+
+        if !self.allow_removals && (!removed_fields.is_empty() || !removed_templates.is_empty()) {
+            return Err(ModelMigrationError::RemovalNotAllowed {
+                fields: removed_fields,
+                templates: removed_templates,
+            });
+        }
+        Ok(plan)
+    }
+}
+
+/// A client, which can send every request of a [`ModelMigration`].
+pub trait ModelMigrationClient:
+    AnkiRequestable<ModelNamesRequest>
+    + AnkiRequestable<ModelFieldNamesRequest>
+    + AnkiRequestable<ModelTemplatesRequest>
+    + AnkiRequestable<ModelStylingRequest>
+    + AnkiRequestable<CreateModelRequest>
+    + AnkiRequestable<ModelFieldRenameRequest>
+    + AnkiRequestable<ModelFieldAddRequest>
+    + AnkiRequestable<ModelFieldRemoveRequest>
+    + AnkiRequestable<ModelFieldRepositionRequest>
+    + AnkiRequestable<ModelTemplateRenameRequest>
+    + AnkiRequestable<ModelTemplateAddRequest>
+    + AnkiRequestable<UpdateModelTemplatesRequest>
+    + AnkiRequestable<ModelTemplateRemoveRequest>
+    + AnkiRequestable<UpdateModelStylingRequest>
+{
+}
+
+impl<C> ModelMigrationClient for C where
+    C: AnkiRequestable<ModelNamesRequest>
+        + AnkiRequestable<ModelFieldNamesRequest>
+        + AnkiRequestable<ModelTemplatesRequest>
+        + AnkiRequestable<ModelStylingRequest>
+        + AnkiRequestable<CreateModelRequest>
+        + AnkiRequestable<ModelFieldRenameRequest>
+        + AnkiRequestable<ModelFieldAddRequest>
+        + AnkiRequestable<ModelFieldRemoveRequest>
+        + AnkiRequestable<ModelFieldRepositionRequest>
+        + AnkiRequestable<ModelTemplateRenameRequest>
+        + AnkiRequestable<ModelTemplateAddRequest>
+        + AnkiRequestable<UpdateModelTemplatesRequest>
+        + AnkiRequestable<ModelTemplateRemoveRequest>
+        + AnkiRequestable<UpdateModelStylingRequest>
+{
+}
+
+/// A client, which can send every request of a [`ModelMigration`] asynchronously.
+pub trait AsyncModelMigrationClient:
+    AsyncAnkiRequestable<ModelNamesRequest>
+    + AsyncAnkiRequestable<ModelFieldNamesRequest>
+    + AsyncAnkiRequestable<ModelTemplatesRequest>
+    + AsyncAnkiRequestable<ModelStylingRequest>
+    + AsyncAnkiRequestable<CreateModelRequest>
+    + AsyncAnkiRequestable<ModelFieldRenameRequest>
+    + AsyncAnkiRequestable<ModelFieldAddRequest>
+    + AsyncAnkiRequestable<ModelFieldRemoveRequest>
+    + AsyncAnkiRequestable<ModelFieldRepositionRequest>
+    + AsyncAnkiRequestable<ModelTemplateRenameRequest>
+    + AsyncAnkiRequestable<ModelTemplateAddRequest>
+    + AsyncAnkiRequestable<UpdateModelTemplatesRequest>
+    + AsyncAnkiRequestable<ModelTemplateRemoveRequest>
+    + AsyncAnkiRequestable<UpdateModelStylingRequest>
+    + Sync
+{
+}
+
+impl<C> AsyncModelMigrationClient for C where
+    C: AsyncAnkiRequestable<ModelNamesRequest>
+        + AsyncAnkiRequestable<ModelFieldNamesRequest>
+        + AsyncAnkiRequestable<ModelTemplatesRequest>
+        + AsyncAnkiRequestable<ModelStylingRequest>
+        + AsyncAnkiRequestable<CreateModelRequest>
+        + AsyncAnkiRequestable<ModelFieldRenameRequest>
+        + AsyncAnkiRequestable<ModelFieldAddRequest>
+        + AsyncAnkiRequestable<ModelFieldRemoveRequest>
+        + AsyncAnkiRequestable<ModelFieldRepositionRequest>
+        + AsyncAnkiRequestable<ModelTemplateRenameRequest>
+        + AsyncAnkiRequestable<ModelTemplateAddRequest>
+        + AsyncAnkiRequestable<UpdateModelTemplatesRequest>
+        + AsyncAnkiRequestable<ModelTemplateRemoveRequest>
+        + AsyncAnkiRequestable<UpdateModelStylingRequest>
+        + Sync
+{
+}
+
+/// Builds the state of a model from the responses of Anki.
+fn state(
+    fields: Vec<String>,
+    templates: HashMap<String, HashMap<String, String>>,
+    css: String,
+) -> ModelState {
+    let templates = templates
+        .into_iter()
+        .map(|(name, mut sides)| {
+            let template = CardTemplate {
+                name: name.clone(),
+                front: sides.remove("Front").unwrap_or_default(),
+                back: sides.remove("Back").unwrap_or_default(),
+            };
+            (name, template)
+        })
+        .collect();
+    ModelState {
+        fields,
+        templates,
+        css,
+    }
+}
+
+/// Returns the moves, which reorder `current` into `desired`, keeping the longest run of fields,
+/// which are already in order, in place.
+fn reposition(current: &[String], desired: &[&str]) -> Vec<(String, usize)> {
+    let positions = current
+        .iter()
+        .map(|field| desired.iter().position(|desired| desired == field))
+        .collect::<Option<Vec<_>>>()
+        .expect("the current fields match the desired fields");
+
+    // The longest increasing subsequence of the desired positions stays in place.
+    let mut lengths = vec![1; positions.len()];
+    let mut previous = vec![None; positions.len()];
+    for i in 0..positions.len() {
+        for j in 0..i {
+            if positions[j] < positions[i] && lengths[j] + 1 > lengths[i] {
+                lengths[i] = lengths[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+    }
+    let mut kept = vec![false; desired.len()];
+    let mut index = (0..positions.len()).max_by_key(|&i| lengths[i]);
+    while let Some(i) = index {
+        kept[positions[i]] = true;
+        index = previous[i];
+    }
+
+    let mut moves = Vec::new();
+    let mut fields = current.to_vec();
+    for (target, field) in desired.iter().enumerate().rev() {
+        if kept[target] {
+            continue;
+        }
+        move_field(&mut fields, field, target);
+        moves.push(((*field).to_string(), target));
+    }
+    if fields == desired {
+        return moves;
+    }
+
+    // Falls back to moving the fields one position after another, which is always correct.
+    let mut moves = Vec::new();
+    let mut fields = current.to_vec();
+    for (target, field) in desired.iter().enumerate() {
+        if fields[target] != *field {
+            move_field(&mut fields, field, target);
+            moves.push(((*field).to_string(), target));
+        }
+    }
+    moves
+}
+
+fn move_field(fields: &mut Vec<String>, field: &str, target: usize) {
+    let index = fields
+        .iter()
+        .position(|existing| existing == field)
+        .expect("the field exists");
+    let field = fields.remove(index);
+    fields.insert(target, field);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake::FakeAnki;
+
+    #[test]
+    fn test_reposition_moves_few_fields() {
+        let current = ["a", "b", "c", "d"].map(String::from);
+        assert_eq!(
+            reposition(&current, &["b", "c", "d", "a"]),
+            [("a".to_string(), 3)]
+        );
+        let current = ["d", "a", "c", "b"].map(String::from);
+        let moves = reposition(&current, &["a", "b", "c", "d"]);
+        assert_eq!(moves.len(), 2);
+    }
+
+    #[test]
+    fn test_migration_against_fake() {
+        let anki = FakeAnki::new();
+        let definition = ModelDefinition::builder("Vocabulary")
+            .field("Word")
+            .field("English")
+            .template("Recognition", "{{Word}}", "{{English}}")
+            .build()
+            .unwrap();
+        let plan = ModelMigration::new(definition).plan(&anki).unwrap();
+        assert!(matches!(
+            plan.operations[..],
+            [ModelOperation::CreateModel(_)]
+        ));
+        plan.apply(&anki).unwrap();
+
+        let definition = ModelDefinition::builder("Vocabulary")
+            .field("Reading")
+            .field("Word")
+            .field("Meaning")
+            .template("Recognition", "{{Word}}", "{{Reading}}<br>{{Meaning}}")
+            .template("Production", "{{Meaning}}", "{{Word}}")
+            .css(".card { color: black; }")
+            .build()
+            .unwrap();
+        let migration = ModelMigration::new(definition).rename_field("English", "Meaning");
+        let plan = migration.plan(&anki).unwrap();
+        assert_eq!(
+            plan.to_string(),
+            "migrate model `Vocabulary`:
+  rename field `English` to `Meaning`
+  add field `Reading` at position 0
+  add template `Production`
+  update the templates [\"Recognition\"]
+  update the styling
+"
+        );
+        plan.apply(&anki).unwrap();
+        assert!(migration.plan(&anki).unwrap().is_empty());
+
+        let definition = ModelDefinition::builder("Vocabulary")
+            .field("Word")
+            .template("Recognition", "{{Word}}", "{{Word}}")
+            .build()
+            .unwrap();
+        let error = ModelMigration::new(definition).plan(&anki).unwrap_err();
+        assert!(matches!(
+            error,
+            ModelMigrationError::RemovalNotAllowed { fields, templates }
+                if fields == ["Reading", "Meaning"] && templates == ["Production"]
+        ));
+    }
+
+    #[test]
+    fn test_fields_are_added_at_their_position() {
+        let anki = FakeAnki::new();
+        let model = |fields: &[&str]| {
+            let mut builder = ModelDefinition::builder("Fields");
+            for field in fields {
+                builder = builder.field(*field);
+            }
+            builder
+                .template("Card", format!("{{{{{}}}}}", fields[0]), "")
+                .build()
+                .unwrap()
+        };
+        let migrate = |fields: &[&str]| {
+            let plan = ModelMigration::new(model(fields))
+                .allow_removals(true)
+                .plan(&anki)
+                .unwrap();
+            plan.apply(&anki).unwrap();
+            let names = anki
+                .request(ModelFieldNamesRequest {
+                    model_name: "Fields".to_string(),
+                })
+                .unwrap();
+            assert_eq!(names, fields);
+            plan
+        };
+        migrate(&["A", "B", "C", "D"]);
+
+        let plan = migrate(&["D", "X", "B", "Y"]);
+        assert!(!plan.operations.iter().any(|operation| matches!(
+            operation,
+            ModelOperation::RepositionField(request) if request.field_name == "X"
+        )));
+        migrate(&["P", "Q"]);
+    }
+}
@@ -35,4 +35,5 @@ impl AnkiRequest for ModelNamesRequest {
 
     const ACTION: &'static str = "modelNames";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
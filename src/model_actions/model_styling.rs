@@ -42,4 +42,5 @@ impl AnkiRequest for ModelStylingRequest {
 
     const ACTION: &'static str = "modelStyling";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -40,4 +40,5 @@ impl AnkiRequest for ModelTemplatesRequest {
 
     const ACTION: &'static str = "modelTemplates";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -0,0 +1,259 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{collections::HashMap, fmt};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{ids::NoteId, AnkiRequest};
+
+/// Parameters for the "addNote" action in `AnkiConnect`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AddNoteRequest {
+    /// The note to add.
+    pub note: AddNoteNote,
+}
+
+/// A note which should be added to the collection.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddNoteNote {
+    /// The deck name for the note.
+    pub deck_name: String,
+    /// The model name for the note.
+    pub model_name: String,
+    /// The fields of the note, keyed by field name.
+    pub fields: NoteFields,
+    /// Options which control how duplicates are handled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<AddNoteOptions>,
+    /// The tags of the note.
+    pub tags: Vec<String>,
+    /// The audio files attached to the note.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub audio: Vec<AddNoteMedia>,
+    /// The video files attached to the note.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub video: Vec<AddNoteMedia>,
+    /// The pictures attached to the note.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub picture: Vec<AddNoteMedia>,
+}
+
+/// The fields of a note, which keep the order in which they were inserted.
+///
+/// It is serialized as a map from field name to value.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct NoteFields(Vec<(String, String)>);
+
+impl NoteFields {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Sets the value of a field, replacing its former value while keeping its position.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let (name, value) = (name.into(), value.into());
+        match self.0.iter_mut().find(|(field, _)| *field == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((name, value)),
+        }
+    }
+
+    /// Sets the value of a field and returns the fields, for chaining.
+    #[must_use]
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    /// Returns the value of a field.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterates over the names and values of the fields in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for NoteFields {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut fields = Self::new();
+        for (name, value) in iter {
+            fields.insert(name, value);
+        }
+        fields
+    }
+}
+
+impl<K: Into<String>, V: Into<String>, const N: usize> From<[(K, V); N]> for NoteFields {
+    fn from(fields: [(K, V); N]) -> Self {
+        fields.into_iter().collect()
+    }
+}
+
+/// Eases the transition from the former `HashMap` fields, whose order is arbitrary.
+impl From<HashMap<String, String>> for NoteFields {
+    fn from(fields: HashMap<String, String>) -> Self {
+        fields.into_iter().collect()
+    }
+}
+
+impl Serialize for NoteFields {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in &self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for NoteFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = NoteFields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map from field names to values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields = NoteFields::new();
+                while let Some((name, value)) = map.next_entry::<String, String>()? {
+                    fields.insert(name, value);
+                }
+                Ok(fields)
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+/// Options for adding a note.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddNoteOptions {
+    /// Allows the note to be added even if it is a duplicate.
+    pub allow_duplicate: bool,
+    /// The scope in which duplicates are searched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_scope: Option<AddNoteDuplicateScope>,
+    /// Additional options for the duplicate check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_scope_options: Option<AddNoteDuplicateScopeOptions>,
+}
+
+/// The scope in which duplicates are searched.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AddNoteDuplicateScope {
+    /// Only check the target deck for duplicates.
+    #[default]
+    Deck,
+    /// Check the whole collection for duplicates.
+    Collection,
+}
+
+/// Additional options for the duplicate check.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddNoteDuplicateScopeOptions {
+    /// The deck which should be checked, defaults to the target deck if [None].
+    pub deck_name: Option<String>,
+    /// Also checks the children of the deck.
+    pub check_children: bool,
+    /// Checks notes of every model, instead of only the model of the note.
+    pub check_all_models: bool,
+}
+
+/// A media file attached to a note, either by `url`, base64 encoded `data` or a local `path`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddNoteMedia {
+    /// The URL to download the file from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The base64 encoded content of the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// The absolute path to the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The filename which is used inside the media folder.
+    pub filename: String,
+    /// Skips the file, if its MD5 hash matches the given value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_hash: Option<String>,
+    /// The fields in which the file should be embedded.
+    pub fields: Vec<String>,
+}
+
+impl AnkiRequest for AddNoteRequest {
+    type Response = NoteId;
+
+    const ACTION: &'static str = "addNote";
+    const VERSION: u8 = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_keep_their_order() {
+        let fields = NoteFields::from([("Front", "犬"), ("Back", "dog"), ("Reading", "いぬ")]);
+        let json = serde_json::to_string(&fields).unwrap();
+        assert_eq!(json, r#"{"Front":"犬","Back":"dog","Reading":"いぬ"}"#);
+        let parsed: NoteFields = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, fields);
+        assert_eq!(parsed.get("Back"), Some("dog"));
+    }
+}
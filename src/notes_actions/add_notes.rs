@@ -0,0 +1,102 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use serde::Serialize;
+
+use crate::{ids::NoteId, notes_actions::add_note::AddNoteNote, AnkiRequest};
+
+/// Parameters for the "addNotes" action in `AnkiConnect`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AddNotesRequest {
+    /// The notes to add.
+    pub notes: Vec<AddNoteNote>,
+}
+
+impl AnkiRequest for AddNotesRequest {
+    /// The IDs of the created notes, [None] if a note could not be created.
+    type Response = Vec<Option<NoteId>>;
+
+    const ACTION: &'static str = "addNotes";
+    const VERSION: u8 = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        notes_actions::add_note::{AddNoteDuplicateScope, AddNoteOptions, NoteFields},
+        parse_response,
+    };
+
+    #[test]
+    fn test_add_notes_to_json() {
+        let request = AddNotesRequest {
+            notes: vec![AddNoteNote {
+                deck_name: "Default".to_string(),
+                model_name: "Basic".to_string(),
+                fields: NoteFields::from([("Front", "front content"), ("Back", "back content")]),
+                options: Some(AddNoteOptions {
+                    allow_duplicate: false,
+                    duplicate_scope: Some(AddNoteDuplicateScope::Deck),
+                    duplicate_scope_options: None,
+                }),
+                tags: vec!["yomichan".to_string()],
+                ..Default::default()
+            }],
+        };
+        assert_eq!(
+            json!({
+                "action": "addNotes",
+                "version": 6,
+                "params": {
+                    "notes": [{
+                        "deckName": "Default",
+                        "modelName": "Basic",
+                        "fields": {
+                            "Front": "front content",
+                            "Back": "back content",
+                        },
+                        "options": {
+                            "allowDuplicate": false,
+                            "duplicateScope": "deck",
+                        },
+                        "tags": ["yomichan"],
+                    }],
+                },
+            }),
+            request.to_json()
+        );
+    }
+
+    #[test]
+    fn test_add_notes_response() {
+        let response = parse_response::<AddNotesRequest>(
+            r#"{"result": [1496198395707, null], "error": null}"#,
+        )
+        .unwrap();
+        assert_eq!(vec![Some(NoteId(1_496_198_395_707)), None], response);
+    }
+}
@@ -0,0 +1,82 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use serde::Serialize;
+
+use crate::{notes_actions::add_note::AddNoteNote, AnkiRequest};
+
+/// Parameters for the "canAddNotes" action in `AnkiConnect`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CanAddNotesRequest {
+    /// The notes to check.
+    pub notes: Vec<AddNoteNote>,
+}
+
+impl AnkiRequest for CanAddNotesRequest {
+    type Response = Vec<bool>;
+
+    const ACTION: &'static str = "canAddNotes";
+    const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{notes_actions::add_note::NoteFields, parse_response};
+
+    #[test]
+    fn test_can_add_notes_to_json() {
+        let request = CanAddNotesRequest {
+            notes: vec![AddNoteNote {
+                deck_name: "Default".to_string(),
+                model_name: "Basic".to_string(),
+                fields: NoteFields::from([("Front", "front content")]),
+                tags: vec![],
+                ..Default::default()
+            }],
+        };
+        assert_eq!(
+            json!({
+                "action": "canAddNotes",
+                "version": 6,
+                "params": {
+                    "notes": [{
+                        "deckName": "Default",
+                        "modelName": "Basic",
+                        "fields": {"Front": "front content"},
+                        "tags": [],
+                    }],
+                },
+            }),
+            request.to_json()
+        );
+        let response =
+            parse_response::<CanAddNotesRequest>(r#"{"result": [true, false], "error": null}"#)
+                .unwrap();
+        assert_eq!(vec![true, false], response);
+    }
+}
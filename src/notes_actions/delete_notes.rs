@@ -23,12 +23,12 @@
 */
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::NoteId, AnkiRequest};
 
 /// Parameters for the "deleteNotes" action in `AnkiConnect`.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct DeleteNotesRequest {
-    pub notes: Vec<usize>,
+    pub notes: Vec<NoteId>,
 }
 
 impl AnkiRequest for DeleteNotesRequest {
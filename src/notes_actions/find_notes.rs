@@ -23,7 +23,7 @@
 */
 use serde::{Deserialize, Serialize};
 
-use crate::AnkiRequest;
+use crate::{ids::NoteId, AnkiRequest};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct FindNotesRequest {
@@ -31,11 +31,12 @@ pub struct FindNotesRequest {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
-pub struct FindNotesResponse(pub Vec<usize>);
+pub struct FindNotesResponse(pub Vec<NoteId>);
 
 impl AnkiRequest for FindNotesRequest {
     type Response = FindNotesResponse;
 
     const ACTION: &'static str = "findNotes";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
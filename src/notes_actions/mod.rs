@@ -21,9 +21,41 @@
 * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 * SOFTWARE.
 */
+/// Creates a note using the given deck and model, with the provided field values and tags. Returns the identifier of the created note on success, and an error on failure.
+pub mod add_note;
+/// Creates multiple notes using the given deck and model, with the provided field values and tags. Returns an array of identifiers of the created notes. In the event of any errors, all errors are gathered and returned.
+pub mod add_notes;
+/// Adds tags to notes by note ID.
+pub mod add_tags;
+/// Accepts an array of objects which define parameters for candidate notes and returns an array of booleans indicating whether or not the parameters at the corresponding index could be used to create a new note.
+pub mod can_add_notes;
+/// Accepts an array of objects which define parameters for candidate notes and returns an array of objects with fields `canAdd` and `error`.
+pub mod can_add_notes_with_error_detail;
+/// Clears all the unused tags in the notes for the current user.
+pub mod clear_unused_tags;
 /// Deletes notes with the given ids. If a note has several cards associated with it, all associated cards will be deleted
 pub mod delete_notes;
 /// Returns an array of note IDs for a given query. Query syntax is documented [here](https://docs.ankiweb.net/searching.html).
 pub mod find_notes;
+/// Get a note's tags by note ID.
+pub mod get_note_tags;
+/// Gets the complete list of tags for the current user.
+pub mod get_tags;
 /// Returns a list of objects containing for each note ID the note fields, tags, note type and the cards belonging to the note.
 pub mod notes_info;
+/// Remove tags from notes by note ID.
+pub mod remove_tags;
+/// Replace tags in notes by note ID.
+pub mod replace_tags;
+/// Replace tags in all the notes for the current user.
+pub mod replace_tags_in_all_notes;
+/// Compares the tags of a note with the desired tags and only adds or removes the tags which differ.
+pub mod set_note_tags;
+/// Modify the fields and/or tags of an existing note. In other words, combines `updateNoteFields` and `updateNoteTags`.
+pub mod update_note;
+/// Modify the fields of an existing note. You can also include audio, video, or picture files which will be added to the note.
+pub mod update_note_fields;
+/// Update the model, fields, and tags of an existing note. This allows you to change the note's model, update its fields with new content, and set new tags.
+pub mod update_note_model;
+/// Set a note's tags by note ID. Old tags will be removed.
+pub mod update_note_tags;
@@ -24,17 +24,17 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::AnkiRequest;
+use crate::{ids::NoteId, AnkiRequest};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct NotesInfoRequest {
-    pub notes: Vec<usize>,
+    pub notes: Vec<NoteId>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NotesInfoResponse {
-    pub note_id: usize,
+    pub note_id: NoteId,
     pub model_name: String,
     pub tags: Vec<String>,
     pub fields: HashMap<String, NotesInfoFieldsResponse>,
@@ -50,4 +50,5 @@ impl AnkiRequest for NotesInfoRequest {
 
     const ACTION: &'static str = "notesInfo";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -0,0 +1,172 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::{
+    ids::NoteId,
+    notes_actions::{
+        add_tags::AddTagsRequest, notes_info::NotesInfoResponse, remove_tags::RemoveTagsRequest,
+    },
+    AnkiRequestable, AsyncAnkiRequestable, Result,
+};
+
+/// The tags which have to be added to and removed from a note to reach the desired tags.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct NoteTagsDiff {
+    /// ID of the note.
+    pub note: NoteId,
+    /// Tags which are missing on the note.
+    pub add: Vec<String>,
+    /// Tags which are on the note, but not desired.
+    pub remove: Vec<String>,
+}
+
+impl NoteTagsDiff {
+    /// Compares the current tags of the note with the desired ones. Like in Anki, tags are
+    /// compared case-insensitively.
+    #[must_use]
+    pub fn new<T: AsRef<str>>(note: &NotesInfoResponse, desired: &[T]) -> Self {
+        let contains =
+            |tags: &[String], tag: &str| tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+        let mut add: Vec<String> = Vec::new();
+        for tag in desired.iter().map(AsRef::as_ref) {
+            if !contains(&note.tags, tag) && !contains(&add, tag) {
+                add.push(tag.to_string());
+            }
+        }
+        let remove = note
+            .tags
+            .iter()
+            .filter(|tag| !desired.iter().any(|d| d.as_ref().eq_ignore_ascii_case(tag)))
+            .cloned()
+            .collect();
+
+        Self {
+            note: note.note_id,
+            add,
+            remove,
+        }
+    }
+
+    /// Returns [true] if the note already has the desired tags.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove.is_empty()
+    }
+
+    /// Returns the "addTags" request, if there are tags to add.
+    #[must_use]
+    pub fn add_request(&self) -> Option<AddTagsRequest> {
+        (!self.add.is_empty()).then(|| AddTagsRequest {
+            notes: vec![self.note],
+            tags: self.add.join(" "),
+        })
+    }
+
+    /// Returns the "removeTags" request, if there are tags to remove.
+    #[must_use]
+    pub fn remove_request(&self) -> Option<RemoveTagsRequest> {
+        (!self.remove.is_empty()).then(|| RemoveTagsRequest {
+            notes: vec![self.note],
+            tags: self.remove.join(" "),
+        })
+    }
+}
+
+/// Brings the tags of the note in line with the desired tags, by only sending the needed
+/// "addTags" and "removeTags" requests. Returns the applied diff.
+///
+/// # Errors
+///
+/// Returns an error if one of the requests fails.
+pub fn set_note_tags<C, T>(
+    client: &C,
+    note: &NotesInfoResponse,
+    desired: &[T],
+) -> Result<NoteTagsDiff>
+where
+    C: AnkiRequestable<AddTagsRequest> + AnkiRequestable<RemoveTagsRequest>,
+    T: AsRef<str>,
+{
+    let diff = NoteTagsDiff::new(note, desired);
+    if let Some(request) = diff.remove_request() {
+        AnkiRequestable::<RemoveTagsRequest>::request(client, request)?;
+    }
+    if let Some(request) = diff.add_request() {
+        AnkiRequestable::<AddTagsRequest>::request(client, request)?;
+    }
+    Ok(diff)
+}
+
+/// Brings the tags of the note in line with the desired tags, like [`set_note_tags`] for
+/// asynchronous clients.
+///
+/// # Errors
+///
+/// Returns an error if one of the requests fails.
+pub async fn set_note_tags_async<C, T>(
+    client: &C,
+    note: &NotesInfoResponse,
+    desired: &[T],
+) -> Result<NoteTagsDiff>
+where
+    C: AsyncAnkiRequestable<AddTagsRequest> + AsyncAnkiRequestable<RemoveTagsRequest> + Sync,
+    T: AsRef<str>,
+{
+    let diff = NoteTagsDiff::new(note, desired);
+    if let Some(request) = diff.remove_request() {
+        AsyncAnkiRequestable::<RemoveTagsRequest>::request(client, request).await?;
+    }
+    if let Some(request) = diff.add_request() {
+        AsyncAnkiRequestable::<AddTagsRequest>::request(client, request).await?;
+    }
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_tags_diff() {
+        let note = NotesInfoResponse {
+            note_id: NoteId(42),
+            tags: vec!["rust".to_string(), "Anki".to_string(), "old".to_string()],
+            ..Default::default()
+        };
+        let diff = NoteTagsDiff::new(&note, &["anki", "rust", "new", "NEW"]);
+        assert_eq!(vec!["new".to_string()], diff.add);
+        assert_eq!(vec!["old".to_string()], diff.remove);
+        assert_eq!(
+            Some(AddTagsRequest {
+                notes: vec![NoteId(42)],
+                tags: "new".to_string(),
+            }),
+            diff.add_request()
+        );
+
+        let diff = NoteTagsDiff::new(&note, &["rust", "anki", "old"]);
+        assert!(diff.is_empty());
+        assert_eq!(None, diff.remove_request());
+    }
+}
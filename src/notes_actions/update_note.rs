@@ -0,0 +1,85 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use serde::Serialize;
+
+use crate::{ids::NoteId, notes_actions::add_note::NoteFields, AnkiRequest};
+
+/// Parameters for the "updateNote" action in `AnkiConnect`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateNoteRequest {
+    /// The note which should be updated.
+    pub note: UpdateNoteNote,
+}
+
+/// The ID of the note with its new fields and/or tags.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateNoteNote {
+    /// The ID of the note.
+    pub id: NoteId,
+    /// The fields which should be updated, keyed by field name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<NoteFields>,
+    /// The new tags of the note, which replace the old ones.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl AnkiRequest for UpdateNoteRequest {
+    type Response = ();
+
+    const ACTION: &'static str = "updateNote";
+    const VERSION: u8 = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_update_note_to_json() {
+        let request = UpdateNoteRequest {
+            note: UpdateNoteNote {
+                id: NoteId(1_514_547_547_030),
+                fields: Some(NoteFields::from([("Front", "new front content")])),
+                tags: None,
+            },
+        };
+        assert_eq!(
+            json!({
+                "action": "updateNote",
+                "version": 6,
+                "params": {
+                    "note": {
+                        "id": 1_514_547_547_030_u64,
+                        "fields": {"Front": "new front content"},
+                    },
+                },
+            }),
+            request.to_json()
+        );
+    }
+}
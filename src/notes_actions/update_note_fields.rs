@@ -0,0 +1,107 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use serde::Serialize;
+
+use crate::{
+    ids::NoteId,
+    notes_actions::add_note::{AddNoteMedia, NoteFields},
+    AnkiRequest,
+};
+
+/// Parameters for the "`updateNoteFields`" action in `AnkiConnect`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateNoteFieldsRequest {
+    /// The note which should be updated.
+    pub note: UpdateNoteFieldsNote,
+}
+
+/// The ID and the new fields of the note.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateNoteFieldsNote {
+    /// The ID of the note.
+    pub id: NoteId,
+    /// The fields which should be updated, keyed by field name.
+    pub fields: NoteFields,
+    /// The audio files attached to the note.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub audio: Vec<AddNoteMedia>,
+    /// The video files attached to the note.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub video: Vec<AddNoteMedia>,
+    /// The pictures attached to the note.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub picture: Vec<AddNoteMedia>,
+}
+
+impl AnkiRequest for UpdateNoteFieldsRequest {
+    type Response = ();
+
+    const ACTION: &'static str = "updateNoteFields";
+    const VERSION: u8 = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_update_note_fields_to_json() {
+        let request = UpdateNoteFieldsRequest {
+            note: UpdateNoteFieldsNote {
+                id: NoteId(1_514_547_547_030),
+                fields: NoteFields::from([("Back", "new back content")]),
+                picture: vec![AddNoteMedia {
+                    url: Some("https://example.com/cat.jpg".to_string()),
+                    filename: "cat.jpg".to_string(),
+                    skip_hash: Some("8d1d2e4d".to_string()),
+                    fields: vec!["Back".to_string()],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            json!({
+                "action": "updateNoteFields",
+                "version": 6,
+                "params": {
+                    "note": {
+                        "id": 1_514_547_547_030_u64,
+                        "fields": {"Back": "new back content"},
+                        "picture": [{
+                            "url": "https://example.com/cat.jpg",
+                            "filename": "cat.jpg",
+                            "skipHash": "8d1d2e4d",
+                            "fields": ["Back"],
+                        }],
+                    },
+                },
+            }),
+            request.to_json()
+        );
+    }
+}
@@ -0,0 +1,89 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use serde::Serialize;
+
+use crate::{ids::NoteId, notes_actions::add_note::NoteFields, AnkiRequest};
+
+/// Parameters for the "`updateNoteModel`" action in `AnkiConnect`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateNoteModelRequest {
+    /// The note which should be updated.
+    pub note: UpdateNoteModelNote,
+}
+
+/// The ID of the note with its new model, fields and tags.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNoteModelNote {
+    /// The ID of the note.
+    pub id: NoteId,
+    /// The name of the new model.
+    pub model_name: String,
+    /// The fields of the note for the new model, keyed by field name.
+    pub fields: NoteFields,
+    /// The new tags of the note.
+    pub tags: Vec<String>,
+}
+
+impl AnkiRequest for UpdateNoteModelRequest {
+    type Response = ();
+
+    const ACTION: &'static str = "updateNoteModel";
+    const VERSION: u8 = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_update_note_model_to_json() {
+        let request = UpdateNoteModelRequest {
+            note: UpdateNoteModelNote {
+                id: NoteId(1_514_547_547_030),
+                model_name: "NewModel".to_string(),
+                fields: NoteFields::from([("NewField1", "new field 1")]),
+                tags: vec!["new".to_string()],
+            },
+        };
+        assert_eq!(
+            json!({
+                "action": "updateNoteModel",
+                "version": 6,
+                "params": {
+                    "note": {
+                        "id": 1_514_547_547_030_u64,
+                        "modelName": "NewModel",
+                        "fields": {"NewField1": "new field 1"},
+                        "tags": ["new"],
+                    },
+                },
+            }),
+            request.to_json()
+        );
+    }
+}
@@ -0,0 +1,127 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{
+    collections::hash_map::RandomState,
+    fmt::Debug,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use crate::Error;
+
+/// Decides whether and when a failed request is sent again. Only requests which are marked as
+/// [`IDEMPOTENT`](crate::AnkiRequest::IDEMPOTENT) are retried.
+pub trait RetryPolicy: Debug + Send + Sync {
+    /// Returns the delay before the next attempt, or [None] if the request shouldn't be retried.
+    /// The `attempt` is the number of the failed attempt, starting at 1.
+    fn retry_delay(&self, attempt: u32, error: &Error) -> Option<Duration>;
+}
+
+/// Retries [transient](Error::is_transient) errors with an exponentially growing delay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The delay after the first failed attempt.
+    pub initial_delay: Duration,
+    /// The upper limit of the delay.
+    pub max_delay: Duration,
+    /// Randomizes every delay between the half and the full delay, so that several clients
+    /// don't retry at the same time.
+    pub jitter: bool,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+        }
+    }
+}
+
+impl ExponentialBackoff {
+    /// Returns the delay after the given failed attempt, without jitter.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_delay(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts || !error.is_transient() {
+            return None;
+        }
+        let delay = self.delay(attempt);
+        if self.jitter {
+            let random = RandomState::new().build_hasher().finish();
+            // The precision loss doesn't matter for a random fraction.
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = random as f64 / u64::MAX as f64;
+            Some(delay.mul_f64(0.5 + fraction / 2.0))
+        } else {
+            Some(delay)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnkiError;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let policy = ExponentialBackoff {
+            jitter: false,
+            ..Default::default()
+        };
+        let transient = Error::Anki(AnkiError::new("findCards", "collection is not available"));
+        let permanent = Error::Anki(AnkiError::new("findCards", "deck was not found: Test"));
+
+        assert_eq!(
+            Some(Duration::from_millis(250)),
+            policy.retry_delay(1, &transient)
+        );
+        assert_eq!(
+            Some(Duration::from_millis(1000)),
+            policy.retry_delay(3, &transient)
+        );
+        assert_eq!(None, policy.retry_delay(5, &transient));
+        assert_eq!(None, policy.retry_delay(1, &permanent));
+        assert_eq!(Duration::from_secs(5), policy.delay(30));
+
+        let jittered = ExponentialBackoff::default()
+            .retry_delay(2, &transient)
+            .unwrap();
+        assert!(jittered >= Duration::from_millis(250) && jittered <= Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,303 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::card_actions::cards_info::CardsInfoResponse;
+
+/// The number of seconds in a day, which is the unit of positive intervals.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+macro_rules! int_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident, default = $default:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        ///
+        /// It is serialized as the plain number used by Anki. Numbers, which are unknown to this
+        /// crate, are kept in `Other`, so nothing is lost when a value is read and written back.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// A number, which is unknown to this crate.
+            Other(i64),
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::$default
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(value: i64) -> Self {
+                match value {
+                    $($value => Self::$variant,)*
+                    other => Self::Other(other),
+                }
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $value,)*
+                    $name::Other(other) => other,
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_i64(i64::from(*self))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                i64::deserialize(deserializer).map(Self::from)
+            }
+        }
+    };
+}
+
+int_enum!(
+    /// The type of a card, which tells how far the card is in its learning.
+    CardType, default = New {
+        /// The card has never been studied.
+        New = 0,
+        /// The card is in its first learning steps.
+        Learning = 1,
+        /// The card has graduated and is reviewed.
+        Review = 2,
+        /// The card has lapsed and is in its relearning steps.
+        Relearning = 3,
+    }
+);
+
+int_enum!(
+    /// The queue of a card, which tells when and how the card is shown next.
+    CardQueue, default = New {
+        /// The card was buried manually.
+        UserBuried = -3,
+        /// The card was buried by the scheduler, e.g. as sibling.
+        SchedBuried = -2,
+        /// The card is suspended.
+        Suspended = -1,
+        /// The card is new.
+        New = 0,
+        /// The card is learned with an interval of less than a day.
+        Learning = 1,
+        /// The card is reviewed.
+        Review = 2,
+        /// The card is learned with an interval of at least one day.
+        DayLearning = 3,
+        /// The card is previewed in a filtered deck.
+        Preview = 4,
+    }
+);
+
+impl CardQueue {
+    /// Returns whether the card is buried manually or by the scheduler.
+    #[must_use]
+    pub const fn is_buried(self) -> bool {
+        matches!(self, Self::UserBuried | Self::SchedBuried)
+    }
+
+    /// Returns whether the card is in one of the learning queues.
+    #[must_use]
+    pub const fn is_learning(self) -> bool {
+        matches!(self, Self::Learning | Self::DayLearning)
+    }
+}
+
+int_enum!(
+    /// The type of a review in the review log.
+    ReviewType, default = Learning {
+        /// The card was reviewed while it was learned.
+        Learning = 0,
+        /// The card was reviewed as normal review.
+        Review = 1,
+        /// The card was reviewed while it was relearned.
+        Relearning = 2,
+        /// The card was reviewed in a filtered deck.
+        Filtered = 3,
+        /// The card was rescheduled manually, e.g. by "Set Due Date".
+        Manual = 4,
+        /// The card was rescheduled, e.g. by changing its FSRS parameters.
+        Rescheduled = 5,
+    }
+);
+
+int_enum!(
+    /// The button, which was pressed to answer a card.
+    ///
+    /// Manual entries in the review log use `Other(0)`.
+    Ease, default = Good {
+        /// The answer was wrong.
+        Again = 1,
+        /// The answer was correct, but difficult.
+        Hard = 2,
+        /// The answer was correct.
+        Good = 3,
+        /// The answer was correct and easy.
+        Easy = 4,
+    }
+);
+
+/// Converts an interval of Anki into a duration.
+///
+/// Negative intervals are in seconds and positive intervals in days.
+#[must_use]
+pub const fn interval_to_duration(interval: isize) -> Duration {
+    if interval < 0 {
+        Duration::from_secs(interval.unsigned_abs() as u64)
+    } else {
+        Duration::from_secs(interval as u64 * SECONDS_PER_DAY)
+    }
+}
+
+/// The interpreted due of a card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Due {
+    /// The position of a new card in the new queue.
+    NewPosition(isize),
+    /// The number of the day on which a review or day learning card is due.
+    ///
+    /// Anki counts the days since the creation of the collection, shifted by the rollover hour.
+    /// `AnkiConnect` exposes neither, so the number isn't converted into a date and is only
+    /// meaningful in comparison with the due of other cards.
+    DueDay(isize),
+    /// The time at which a learning card is due.
+    DueAt(SystemTime),
+}
+
+/// The interpreted scheduling state of a card, which is returned by
+/// [`CardsInfoResponse::schedule`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardSchedule {
+    /// The type of the card.
+    pub card_type: CardType,
+    /// The queue of the card.
+    pub queue: CardQueue,
+    /// The due of the card.
+    pub due: Due,
+    /// The current interval of the card.
+    pub interval: Duration,
+    /// The ease of the card in percent, e.g. `250.0`, or `None` for new cards.
+    pub ease: Option<f64>,
+    /// The number of repetitions of the card.
+    pub reps: usize,
+    /// The number of lapses of the card.
+    pub lapses: usize,
+}
+
+impl From<&CardsInfoResponse> for CardSchedule {
+    fn from(card: &CardsInfoResponse) -> Self {
+        Self {
+            card_type: card.type_field,
+            queue: card.queue,
+            due: interpret_due(card.type_field, card.queue, card.due),
+            interval: interval_to_duration(card.interval),
+            ease: (card.factor > 0).then(|| card.factor as f64 / 10.0),
+            reps: card.reps,
+            lapses: card.lapses,
+        }
+    }
+}
+
+/// Interprets the due of a card by its queue, or by its type if it is suspended or buried.
+fn interpret_due(card_type: CardType, queue: CardQueue, due: isize) -> Due {
+    /// Dues above this are timestamps, because no collection is that many days old.
+    const MIN_TIMESTAMP: isize = 1_000_000_000;
+
+    let timestamp = || Due::DueAt(UNIX_EPOCH + Duration::from_secs(due.unsigned_abs() as u64));
+    match (queue, card_type) {
+        (CardQueue::New, _) => Due::NewPosition(due),
+        (CardQueue::Learning | CardQueue::Preview, _) => timestamp(),
+        (CardQueue::Review | CardQueue::DayLearning, _) => Due::DueDay(due),
+        (_, CardType::New) => Due::NewPosition(due),
+        _ if due >= MIN_TIMESTAMP => timestamp(),
+        _ => Due::DueDay(due),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_is_lossless() {
+        let queues: Vec<CardQueue> = serde_json::from_str("[-3, -2, -1, 0, 3, 7]").unwrap();
+        assert_eq!(
+            queues,
+            [
+                CardQueue::UserBuried,
+                CardQueue::SchedBuried,
+                CardQueue::Suspended,
+                CardQueue::New,
+                CardQueue::DayLearning,
+                CardQueue::Other(7),
+            ]
+        );
+        assert_eq!(serde_json::to_string(&queues).unwrap(), "[-3,-2,-1,0,3,7]");
+        assert_eq!(serde_json::to_string(&Ease::Hard).unwrap(), "2");
+        assert_eq!(serde_json::from_str::<Ease>("0").unwrap(), Ease::Other(0));
+    }
+
+    #[test]
+    fn test_schedule() {
+        let card = CardsInfoResponse {
+            type_field: CardType::Relearning,
+            queue: CardQueue::Suspended,
+            due: 1_700_000_000,
+            interval: -600,
+            factor: 2350,
+            ..Default::default()
+        };
+        let schedule = card.schedule();
+        assert_eq!(
+            schedule.due,
+            Due::DueAt(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert_eq!(schedule.interval, Duration::from_secs(600));
+        assert_eq!(schedule.ease, Some(235.0));
+
+        let card = CardsInfoResponse {
+            type_field: CardType::Review,
+            queue: CardQueue::SchedBuried,
+            due: 1200,
+            interval: 3,
+            ..Default::default()
+        };
+        let schedule = card.schedule();
+        assert_eq!(schedule.due, Due::DueDay(1200));
+        assert_eq!(schedule.interval, Duration::from_secs(3 * SECONDS_PER_DAY));
+        assert_eq!(schedule.ease, None);
+    }
+}
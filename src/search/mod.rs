@@ -0,0 +1,542 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use crate::{
+    ids::{CardId, NoteId},
+    prelude::{FindCardsRequest, FindNotesRequest, GuiBrowseRequest},
+};
+
+mod parser;
+
+pub use parser::{parse, SearchParseError};
+
+/// A value in a search term, which is kept in the escaped form of the Anki search syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchText(String);
+
+impl SearchText {
+    /// Escapes the text, so it is matched literally. The wildcards `*` and `_`, backslashes and
+    /// double quotes are escaped.
+    #[must_use]
+    pub fn literal(text: &str) -> Self {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if matches!(c, '\\' | '"' | '*' | '_') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        Self(escaped)
+    }
+
+    /// Keeps the text as it is, so `*` and `_` can be used as wildcards. The text has to be
+    /// escaped already.
+    #[must_use]
+    pub fn pattern(text: impl Into<String>) -> Self {
+        Self(text.into())
+    }
+
+    /// Returns the escaped text.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for SearchText {
+    fn from(text: &str) -> Self {
+        Self::literal(text)
+    }
+}
+
+impl From<String> for SearchText {
+    fn from(text: String) -> Self {
+        Self::literal(&text)
+    }
+}
+
+/// The state of a card, which is searched with `is:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CardState {
+    Due,
+    New,
+    Learn,
+    Review,
+    Suspended,
+    Buried,
+}
+
+impl CardState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Due => "due",
+            Self::New => "new",
+            Self::Learn => "learn",
+            Self::Review => "review",
+            Self::Suspended => "suspended",
+            Self::Buried => "buried",
+        }
+    }
+}
+
+/// The flag of a card, which is searched with `flag:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flag {
+    None = 0,
+    Red = 1,
+    Orange = 2,
+    Green = 3,
+    Blue = 4,
+    Pink = 5,
+    Turquoise = 6,
+    Purple = 7,
+}
+
+/// A property of a card, which is compared with `prop:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Property {
+    /// The interval in days.
+    Interval,
+    /// The days until the card is due, negative if it is overdue.
+    Due,
+    /// The number of reviews.
+    Reps,
+    /// The number of lapses.
+    Lapses,
+    /// The ease factor, e.g. `2.5`.
+    Ease,
+    /// The position of a new card.
+    Position,
+}
+
+impl Property {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Interval => "ivl",
+            Self::Due => "due",
+            Self::Reps => "reps",
+            Self::Lapses => "lapses",
+            Self::Ease => "ease",
+            Self::Position => "pos",
+        }
+    }
+}
+
+/// The operator, which compares a [`Property`] with a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Equal => "=",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+/// A search query for Anki, which renders to the escaped search syntax with [`Display`].
+///
+/// ```
+/// use anki_bridge::search::{CardState, Comparison, Property, Query};
+///
+/// let query = Query::deck("Japanese::JLPT N5")
+///     .and(Query::tag("verb").or(Query::tag("adjective")))
+///     .and(!Query::is(CardState::Suspended))
+///     .and(Query::prop(Property::Interval, Comparison::GreaterOrEqual, 21.0));
+/// assert_eq!(
+///     query.to_string(),
+///     r#""deck:Japanese::JLPT N5" (tag:verb or tag:adjective) -is:suspended prop:ivl>=21"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Matches text in any field.
+    Text(SearchText),
+    /// `deck:`, which includes the children of the deck.
+    Deck(SearchText),
+    /// `tag:`, which includes the child tags.
+    Tag(SearchText),
+    /// `note:`, which matches the name of the note type.
+    NoteType(SearchText),
+    /// `card:`, which matches the name of the card template.
+    Card(SearchText),
+    /// `field:value`, which matches the whole content of the field.
+    Field { name: SearchText, value: SearchText },
+    /// `is:`
+    Is(CardState),
+    /// `flag:`
+    Flag(Flag),
+    /// `prop:`
+    Prop {
+        property: Property,
+        comparison: Comparison,
+        value: f64,
+    },
+    /// `rated:`, which matches cards answered in the last days, optionally with the given ease.
+    Rated { days: u32, ease: Option<u8> },
+    /// `added:`, which matches cards added in the last days.
+    Added(u32),
+    /// `edited:`, which matches cards whose note was edited in the last days.
+    Edited(u32),
+    /// `nid:`
+    NoteIds(Vec<NoteId>),
+    /// `cid:`
+    CardIds(Vec<CardId>),
+    /// All queries have to match, an empty list matches every card.
+    And(Vec<Query>),
+    /// At least one query has to match, an empty list matches no card.
+    Or(Vec<Query>),
+    /// The query must not match.
+    Not(Box<Query>),
+    /// A part of a query, which is rendered as it is. Inside a group or a negation it is wrapped in
+    /// parentheses, so its operators don't leak into the surrounding query.
+    Raw(String),
+}
+
+impl Query {
+    /// Matches every card.
+    #[must_use]
+    pub fn all() -> Self {
+        Self::And(Vec::new())
+    }
+
+    #[must_use]
+    pub fn text(text: impl Into<SearchText>) -> Self {
+        Self::Text(text.into())
+    }
+
+    #[must_use]
+    pub fn deck(name: impl Into<SearchText>) -> Self {
+        Self::Deck(name.into())
+    }
+
+    #[must_use]
+    pub fn tag(tag: impl Into<SearchText>) -> Self {
+        Self::Tag(tag.into())
+    }
+
+    #[must_use]
+    pub fn note_type(name: impl Into<SearchText>) -> Self {
+        Self::NoteType(name.into())
+    }
+
+    #[must_use]
+    pub fn card(template: impl Into<SearchText>) -> Self {
+        Self::Card(template.into())
+    }
+
+    #[must_use]
+    pub fn field(name: impl Into<SearchText>, value: impl Into<SearchText>) -> Self {
+        Self::Field {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn is(state: CardState) -> Self {
+        Self::Is(state)
+    }
+
+    #[must_use]
+    pub fn flag(flag: Flag) -> Self {
+        Self::Flag(flag)
+    }
+
+    #[must_use]
+    pub fn prop(property: Property, comparison: Comparison, value: f64) -> Self {
+        Self::Prop {
+            property,
+            comparison,
+            value,
+        }
+    }
+
+    #[must_use]
+    pub fn rated(days: u32, ease: Option<u8>) -> Self {
+        Self::Rated { days, ease }
+    }
+
+    #[must_use]
+    pub fn added(days: u32) -> Self {
+        Self::Added(days)
+    }
+
+    #[must_use]
+    pub fn edited(days: u32) -> Self {
+        Self::Edited(days)
+    }
+
+    #[must_use]
+    pub fn note_ids<I: Into<NoteId>>(ids: impl IntoIterator<Item = I>) -> Self {
+        Self::NoteIds(ids.into_iter().map(Into::into).collect())
+    }
+
+    #[must_use]
+    pub fn card_ids<I: Into<CardId>>(ids: impl IntoIterator<Item = I>) -> Self {
+        Self::CardIds(ids.into_iter().map(Into::into).collect())
+    }
+
+    /// Combines both queries, so both have to match.
+    #[must_use]
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            Self::And(mut queries) => {
+                queries.push(other);
+                Self::And(queries)
+            }
+            query => Self::And(vec![query, other]),
+        }
+    }
+
+    /// Combines both queries, so at least one has to match.
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            Self::Or(mut queries) => {
+                queries.push(other);
+                Self::Or(queries)
+            }
+            query => Self::Or(vec![query, other]),
+        }
+    }
+
+    /// Renders the query as part of an `and` or `or` group. Nested groups keep their
+    /// parentheses, even of the same kind, so parsing the result yields the same nesting again.
+    /// Raw queries are wrapped as well, as they may contain operators of their own.
+    fn fmt_grouped(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::And(queries) | Self::Or(queries) if queries.len() > 1 => write!(f, "({self})"),
+            Self::Raw(_) => write!(f, "({self})"),
+            _ => write!(f, "{self}"),
+        }
+    }
+}
+
+impl std::ops::Not for Query {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        match self {
+            Self::Not(query) => *query,
+            query => Self::Not(Box::new(query)),
+        }
+    }
+}
+
+/// Writes a term, which is quoted if it contains whitespace or parentheses.
+fn write_term(f: &mut Formatter<'_>, term: &str) -> fmt::Result {
+    if term.is_empty()
+        || term
+            .chars()
+            .any(|c| c.is_whitespace() || c == '(' || c == ')')
+        || term.eq_ignore_ascii_case("and")
+        || term.eq_ignore_ascii_case("or")
+    {
+        write!(f, "\"{term}\"")
+    } else {
+        f.write_str(term)
+    }
+}
+
+/// Escapes the colons and a leading `-` of a text term, which would be read as a qualifier or a
+/// negation otherwise.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    if text.starts_with('-') {
+        escaped.push('\\');
+    }
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            escaped.push(c);
+            if let Some(next) = chars.next() {
+                escaped.push(next);
+            }
+            continue;
+        }
+        if c == ':' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn join_ids<T: Display>(ids: &[T]) -> String {
+    ids.iter().fold(String::new(), |mut joined, id| {
+        if !joined.is_empty() {
+            joined.push(',');
+        }
+        let _ = write!(joined, "{id}");
+        joined
+    })
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => write_term(f, &escape_text(text.as_str())),
+            Self::Deck(name) => write_term(f, &format!("deck:{}", name.as_str())),
+            Self::Tag(tag) => write_term(f, &format!("tag:{}", tag.as_str())),
+            Self::NoteType(name) => write_term(f, &format!("note:{}", name.as_str())),
+            Self::Card(template) => write_term(f, &format!("card:{}", template.as_str())),
+            Self::Field { name, value } => {
+                write_term(f, &format!("{}:{}", name.as_str(), value.as_str()))
+            }
+            Self::Is(state) => write!(f, "is:{}", state.as_str()),
+            Self::Flag(flag) => write!(f, "flag:{}", *flag as u8),
+            Self::Prop {
+                property,
+                comparison,
+                value,
+            } => write!(
+                f,
+                "prop:{}{}{value}",
+                property.as_str(),
+                comparison.as_str()
+            ),
+            Self::Rated { days, ease: None } => write!(f, "rated:{days}"),
+            Self::Rated {
+                days,
+                ease: Some(ease),
+            } => write!(f, "rated:{days}:{ease}"),
+            Self::Added(days) => write!(f, "added:{days}"),
+            Self::Edited(days) => write!(f, "edited:{days}"),
+            Self::NoteIds(ids) => write!(f, "nid:{}", join_ids(ids)),
+            Self::CardIds(ids) => write!(f, "cid:{}", join_ids(ids)),
+            Self::And(queries) if queries.is_empty() => f.write_str("deck:*"),
+            Self::Or(queries) if queries.is_empty() => f.write_str("-deck:*"),
+            Self::And(queries) | Self::Or(queries) => {
+                let is_and = matches!(self, Self::And(_));
+                for (index, query) in queries.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(if is_and { " " } else { " or " })?;
+                    }
+                    query.fmt_grouped(f)?;
+                }
+                Ok(())
+            }
+            Self::Not(query) => match query.as_ref() {
+                Self::And(queries) | Self::Or(queries) if queries.len() > 1 => {
+                    write!(f, "-({query})")
+                }
+                Self::Raw(_) => write!(f, "-({query})"),
+                query => write!(f, "-{query}"),
+            },
+            Self::Raw(query) => f.write_str(query),
+        }
+    }
+}
+
+impl From<Query> for String {
+    fn from(query: Query) -> Self {
+        query.to_string()
+    }
+}
+
+impl From<Query> for FindCardsRequest {
+    fn from(query: Query) -> Self {
+        Self {
+            query: query.to_string(),
+        }
+    }
+}
+
+impl From<Query> for FindNotesRequest {
+    fn from(query: Query) -> Self {
+        Self {
+            query: query.to_string(),
+        }
+    }
+}
+
+impl From<Query> for GuiBrowseRequest {
+    fn from(query: Query) -> Self {
+        Self {
+            query: query.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            Query::deck("My_Deck*: \"Draft\"").to_string(),
+            r#""deck:My\_Deck\*: \"Draft\"""#
+        );
+        assert_eq!(Query::tag("a::b").to_string(), "tag:a::b");
+        assert_eq!(
+            Query::field("Back Extra", "x").to_string(),
+            r#""Back Extra:x""#
+        );
+        assert_eq!(Query::text("-a:b").to_string(), r"\-a\:b");
+        assert_eq!(Query::text("or").to_string(), r#""or""#);
+        assert_eq!(
+            Query::deck(SearchText::pattern("Japanese::*")).to_string(),
+            "deck:Japanese::*"
+        );
+    }
+
+    #[test]
+    fn test_grouping() {
+        let query = Query::deck("A")
+            .or(Query::deck("B"))
+            .and(!Query::tag("x").and(Query::tag("y")))
+            .or(Query::note_ids([NoteId(1), NoteId(2)]));
+        assert_eq!(
+            query.to_string(),
+            "((deck:A or deck:B) -(tag:x tag:y)) or nid:1,2"
+        );
+        assert_eq!(Query::all().to_string(), "deck:*");
+        assert_eq!((!!Query::is(CardState::New)).to_string(), "is:new");
+    }
+
+    #[test]
+    fn test_raw_grouping() {
+        let raw = || Query::Raw("b or c".to_string());
+        assert_eq!(raw().to_string(), "b or c");
+        assert_eq!(Query::text("a").and(raw()).to_string(), "a (b or c)");
+        assert_eq!(Query::text("a").or(raw()).to_string(), "a or (b or c)");
+        assert_eq!((!raw()).to_string(), "-(b or c)");
+    }
+}
@@ -0,0 +1,472 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{iter::Peekable, str::FromStr, vec::IntoIter};
+
+use thiserror::Error;
+
+use crate::ids::{CardId, NoteId};
+
+use super::{CardState, Comparison, Flag, Property, Query, SearchText};
+
+/// Qualifiers which Anki supports, but which aren't modeled by [`Query`]. They are kept as
+/// [`Query::Raw`].
+const RAW_QUALIFIERS: &[&str] = &[
+    "did",
+    "dupe",
+    "introduced",
+    "mid",
+    "nc",
+    "preset",
+    "re",
+    "resched",
+    "w",
+];
+
+/// Errors which can occur while parsing a search query.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SearchParseError {
+    /// A double quote isn't closed.
+    #[error("unterminated quote")]
+    UnterminatedQuote,
+
+    /// A parenthesis isn't closed or opened.
+    #[error("unbalanced parenthesis")]
+    UnbalancedParenthesis,
+
+    /// A pair of parentheses doesn't contain a query.
+    #[error("empty group")]
+    EmptyGroup,
+
+    /// An operator like `or` or `-` isn't followed or preceded by a query.
+    #[error("`{0}` is missing a search term")]
+    MissingTerm(String),
+
+    /// A search term has an invalid value, e.g. `flag:9` or `nid:abc`.
+    #[error("invalid search term `{term}`: {reason}")]
+    InvalidTerm { term: String, reason: &'static str },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    /// A `-` in front of a group.
+    Not,
+    Term {
+        /// The term without the quotes, but with the backslash escapes.
+        text: String,
+        /// The term as written in the query.
+        raw: String,
+        negated: bool,
+    },
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, SearchParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+    while let Some(&(_, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            _ => {
+                let mut negated = false;
+                if c == '-' {
+                    chars.next();
+                    match chars.peek() {
+                        Some((_, '(')) => {
+                            tokens.push(Token::Not);
+                            continue;
+                        }
+                        Some((_, c)) if !c.is_whitespace() && *c != ')' => negated = true,
+                        _ => return Err(SearchParseError::MissingTerm("-".to_string())),
+                    }
+                }
+                let term_start = chars.peek().map_or(query.len(), |(index, _)| *index);
+                let mut text = String::new();
+                let mut quoted = false;
+                while let Some(&(_, c)) = chars.peek() {
+                    if !quoted && (c.is_whitespace() || c == '(' || c == ')') {
+                        break;
+                    }
+                    chars.next();
+                    match c {
+                        '"' => quoted = !quoted,
+                        '\\' => {
+                            text.push(c);
+                            if let Some((_, escaped)) = chars.next() {
+                                text.push(escaped);
+                            }
+                        }
+                        c => text.push(c),
+                    }
+                }
+                if quoted {
+                    return Err(SearchParseError::UnterminatedQuote);
+                }
+                let end = chars.peek().map_or(query.len(), |(index, _)| *index);
+                let raw = query[term_start..end].to_string();
+                let keyword = (!negated && raw == text).then(|| text.to_lowercase());
+                tokens.push(match keyword.as_deref() {
+                    Some("and") => Token::And,
+                    Some("or") => Token::Or,
+                    _ => Token::Term { text, raw, negated },
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Peekable<IntoIter<Token>>,
+}
+
+impl Parser {
+    fn parse_or(&mut self) -> Result<Query, SearchParseError> {
+        let mut queries = vec![self.parse_and("or")?];
+        while self.tokens.next_if_eq(&Token::Or).is_some() {
+            queries.push(self.parse_and("or")?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::Or(queries)
+        })
+    }
+
+    fn parse_and(&mut self, operator: &str) -> Result<Query, SearchParseError> {
+        let mut queries = Vec::new();
+        loop {
+            match self.tokens.peek() {
+                None | Some(Token::Close | Token::Or) => break,
+                Some(Token::And) => {
+                    self.tokens.next();
+                    if queries.is_empty() {
+                        return Err(SearchParseError::MissingTerm("and".to_string()));
+                    }
+                    if matches!(self.tokens.peek(), None | Some(Token::Close | Token::Or)) {
+                        return Err(SearchParseError::MissingTerm("and".to_string()));
+                    }
+                }
+                Some(_) => queries.push(self.parse_unary()?),
+            }
+        }
+        match queries.len() {
+            0 => Err(SearchParseError::MissingTerm(operator.to_string())),
+            1 => Ok(queries.remove(0)),
+            _ => Ok(Query::And(queries)),
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, SearchParseError> {
+        match self.tokens.next() {
+            Some(Token::Not) => Ok(!self.parse_group()?),
+            Some(Token::Open) => self.parse_group_content(),
+            Some(Token::Term { text, raw, negated }) => {
+                let term = term(&text, raw)?;
+                Ok(if negated { !term } else { term })
+            }
+            _ => Err(SearchParseError::UnbalancedParenthesis),
+        }
+    }
+
+    fn parse_group(&mut self) -> Result<Query, SearchParseError> {
+        match self.tokens.next() {
+            Some(Token::Open) => self.parse_group_content(),
+            _ => Err(SearchParseError::MissingTerm("-".to_string())),
+        }
+    }
+
+    fn parse_group_content(&mut self) -> Result<Query, SearchParseError> {
+        if self.tokens.next_if_eq(&Token::Close).is_some() {
+            return Err(SearchParseError::EmptyGroup);
+        }
+        let query = self.parse_or()?;
+        match self.tokens.next() {
+            Some(Token::Close) => Ok(query),
+            _ => Err(SearchParseError::UnbalancedParenthesis),
+        }
+    }
+}
+
+/// Returns the index of the first colon, which isn't escaped.
+fn qualifier_end(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ':' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn invalid(text: &str, reason: &'static str) -> SearchParseError {
+    SearchParseError::InvalidTerm {
+        term: text.to_string(),
+        reason,
+    }
+}
+
+fn term(text: &str, raw: String) -> Result<Query, SearchParseError> {
+    let Some(colon) = qualifier_end(text) else {
+        return Ok(Query::Text(SearchText(unescape_text(text))));
+    };
+    let (key, value) = (&text[..colon], &text[colon + 1..]);
+    let days = || {
+        value
+            .parse::<u32>()
+            .map_err(|_| invalid(text, "expected a number of days"))
+    };
+    let ids = || {
+        value
+            .split(',')
+            .map(|id| id.trim().parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid(text, "expected ids separated by commas"))
+    };
+    let pattern = || SearchText::pattern(value);
+    Ok(match key.to_lowercase().as_str() {
+        "deck" => Query::Deck(pattern()),
+        "tag" => Query::Tag(pattern()),
+        "note" => Query::NoteType(pattern()),
+        "card" => Query::Card(pattern()),
+        "is" => card_state(value).map_or(Query::Raw(raw), Query::Is),
+        "flag" => {
+            Query::Flag(flag(value).ok_or_else(|| invalid(text, "expected a flag from 0 to 7"))?)
+        }
+        "prop" => prop(value).unwrap_or(Query::Raw(raw)),
+        "rated" => match value.split_once(':') {
+            Some((days, ease)) => Query::Rated {
+                days: days
+                    .parse()
+                    .map_err(|_| invalid(text, "expected a number of days"))?,
+                ease: Some(
+                    ease.parse()
+                        .ok()
+                        .filter(|ease| (1..=4).contains(ease))
+                        .ok_or_else(|| invalid(text, "expected an ease from 1 to 4"))?,
+                ),
+            },
+            None => Query::Rated {
+                days: days()?,
+                ease: None,
+            },
+        },
+        "added" => Query::Added(days()?),
+        "edited" => Query::Edited(days()?),
+        "nid" => Query::NoteIds(ids()?.into_iter().map(NoteId).collect()),
+        "cid" => Query::CardIds(ids()?.into_iter().map(CardId).collect()),
+        key if RAW_QUALIFIERS.contains(&key) => Query::Raw(raw),
+        _ => Query::Field {
+            name: SearchText::pattern(key),
+            value: pattern(),
+        },
+    })
+}
+
+/// Removes the escapes of colons and a leading `-`, which are added again by the rendering.
+fn unescape_text(text: &str) -> String {
+    let text = text
+        .strip_prefix("\\-")
+        .map_or(text.to_string(), |rest| format!("-{rest}"));
+    text.replace("\\:", ":")
+}
+
+fn card_state(value: &str) -> Option<CardState> {
+    Some(match value.to_lowercase().as_str() {
+        "due" => CardState::Due,
+        "new" => CardState::New,
+        "learn" => CardState::Learn,
+        "review" => CardState::Review,
+        "suspended" => CardState::Suspended,
+        "buried" => CardState::Buried,
+        _ => return None,
+    })
+}
+
+fn flag(value: &str) -> Option<Flag> {
+    Some(match value {
+        "0" => Flag::None,
+        "1" => Flag::Red,
+        "2" => Flag::Orange,
+        "3" => Flag::Green,
+        "4" => Flag::Blue,
+        "5" => Flag::Pink,
+        "6" => Flag::Turquoise,
+        "7" => Flag::Purple,
+        _ => return None,
+    })
+}
+
+fn prop(value: &str) -> Option<Query> {
+    let split = value.find(['=', '!', '<', '>'])?;
+    let (property, rest) = value.split_at(split);
+    let property = match property.to_lowercase().as_str() {
+        "ivl" => Property::Interval,
+        "due" => Property::Due,
+        "reps" => Property::Reps,
+        "lapses" => Property::Lapses,
+        "ease" => Property::Ease,
+        "pos" => Property::Position,
+        _ => return None,
+    };
+    let (comparison, number) = [
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("=", Comparison::Equal),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ]
+    .into_iter()
+    .find_map(|(operator, comparison)| Some((comparison, rest.strip_prefix(operator)?)))?;
+    Some(Query::Prop {
+        property,
+        comparison,
+        value: number.parse().ok()?,
+    })
+}
+
+/// Parses an Anki search query. An empty query matches every card, like [`Query::all`].
+///
+/// Parsing the rendered query results in the same query again, including nested groups, so
+/// queries can be normalized and transformed before they are sent. Only groups with less than two
+/// queries come back simplified, e.g. `And([a])` as `a`.
+///
+/// ```
+/// use anki_bridge::search::{parse, Query};
+///
+/// let query = parse(r#"tag:verb or "deck:JLPT N5""#).unwrap();
+/// let scoped = Query::deck("Japanese").and(query);
+/// assert_eq!(scoped.to_string(), r#"deck:Japanese (tag:verb or "deck:JLPT N5")"#);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the quotes or parentheses are unbalanced, an operator is missing a term or
+/// a qualifier has an invalid value.
+pub fn parse(query: &str) -> Result<Query, SearchParseError> {
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        return Ok(Query::all());
+    }
+    let mut parser = Parser {
+        tokens: tokens.into_iter().peekable(),
+    };
+    let parsed = parser.parse_or()?;
+    match parser.tokens.next() {
+        None => Ok(parsed),
+        Some(_) => Err(SearchParseError::UnbalancedParenthesis),
+    }
+}
+
+impl FromStr for Query {
+    type Err = SearchParseError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        parse(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(r#"deck:Japanese -tag:verb (is:due or "Back Extra:a b") prop:ivl>=21"#).unwrap(),
+            Query::And(vec![
+                Query::deck("Japanese"),
+                !Query::tag("verb"),
+                Query::is(CardState::Due).or(Query::field("Back Extra", "a b")),
+                Query::prop(Property::Interval, Comparison::GreaterOrEqual, 21.0),
+            ])
+        );
+        assert_eq!(
+            parse(r"a b or c and -(d e)").unwrap(),
+            Query::And(vec![Query::text("a"), Query::text("b")]).or(Query::And(vec![
+                Query::text("c"),
+                !Query::And(vec![Query::text("d"), Query::text("e")]),
+            ]))
+        );
+        assert_eq!(
+            parse("dupe:123,abc").unwrap(),
+            Query::Raw("dupe:123,abc".to_string())
+        );
+        assert_eq!(parse("rated:7:3").unwrap(), Query::rated(7, Some(3)));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for query in [
+            r#""deck:My\_Deck\*: \"Draft\"" -tag:a::b"#,
+            r#"(deck:A or deck:B) -(tag:x tag:y) or nid:1,2"#,
+            r"\-a\:b front:*dog* card:2 flag:3 rated:1 added:7 edited:2 cid:5",
+            r#"is:buried-sibling "w:word" note:Basic prop:ease<2.5"#,
+        ] {
+            let parsed = parse(query).unwrap();
+            assert_eq!(parse(&parsed.to_string()).unwrap(), parsed, "{query}");
+        }
+
+        let (a, b, c) = (Query::text("a"), Query::text("b"), Query::text("c"));
+        for query in [
+            Query::And(vec![a.clone(), Query::And(vec![b.clone(), c.clone()])]),
+            Query::Or(vec![a, Query::Or(vec![b, c])]),
+        ] {
+            assert_eq!(parse(&query.to_string()).unwrap(), query, "{query}");
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("\"deck:a"), Err(SearchParseError::UnterminatedQuote));
+        assert_eq!(parse("(a"), Err(SearchParseError::UnbalancedParenthesis));
+        assert_eq!(parse("a)"), Err(SearchParseError::UnbalancedParenthesis));
+        assert_eq!(parse("()"), Err(SearchParseError::EmptyGroup));
+        assert_eq!(
+            parse("a or"),
+            Err(SearchParseError::MissingTerm("or".to_string()))
+        );
+        assert!(matches!(
+            parse("flag:9"),
+            Err(SearchParseError::InvalidTerm { .. })
+        ));
+    }
+}
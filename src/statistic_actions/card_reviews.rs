@@ -25,27 +25,59 @@
 use serde::Serialize;
 use serde_tuple::Deserialize_tuple;
 
-use crate::AnkiRequest;
+use crate::{
+    ids::{CardId, ReviewId},
+    scheduling::{Ease, ReviewType},
+    timestamps::Timestamp,
+    AnkiRequest,
+};
 
 /// Parameters for the "cardReviews" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CardReviewsRequest {
     pub start: String,
     #[serde(rename = "startID")]
-    pub start_id: usize,
+    pub start_id: ReviewId,
 }
 
+impl CardReviewsRequest {
+    /// Sets `start_id`, so only reviews after the given time are returned.
+    #[must_use]
+    pub fn since<T: Timestamp>(mut self, time: &T) -> Self {
+        self.start_id = ReviewId(time.unix_millis().max(0));
+        self
+    }
+}
+
+/// A review from the review log.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize_tuple)]
 pub struct CardReviewsResponse {
-    review_time: usize,
-    card_id: usize,
-    usn: isize,
-    button_pressed: usize,
-    new_interval: isize,
-    previous_interval: isize,
-    new_factor: usize,
-    review_duration: usize,
-    review_type: usize,
+    /// The id of the review, which is its time in milliseconds.
+    pub review_time: ReviewId,
+    /// The id of the reviewed card.
+    pub card_id: CardId,
+    /// The update sequence number.
+    pub usn: isize,
+    /// The button, which was pressed.
+    pub button_pressed: Ease,
+    /// The new interval, which is negative for seconds and positive for days.
+    pub new_interval: isize,
+    /// The previous interval, which is negative for seconds and positive for days.
+    pub previous_interval: isize,
+    /// The new ease factor in permille.
+    pub new_factor: usize,
+    /// The duration of the review in milliseconds.
+    pub review_duration: usize,
+    /// The type of the review.
+    pub review_type: ReviewType,
+}
+
+impl CardReviewsResponse {
+    /// Returns the time of the review.
+    #[must_use]
+    pub fn reviewed_at<T: Timestamp>(&self) -> Option<T> {
+        self.review_time.reviewed_at()
+    }
 }
 
 impl AnkiRequest for CardReviewsRequest {
@@ -53,4 +85,5 @@ impl AnkiRequest for CardReviewsRequest {
 
     const ACTION: &'static str = "cardReviews";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
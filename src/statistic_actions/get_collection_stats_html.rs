@@ -38,4 +38,5 @@ impl AnkiRequest for GetCollectionStatsHTMLRequest {
 
     const ACTION: &'static str = "getCollectionStatsHTML";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
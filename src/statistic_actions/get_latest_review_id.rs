@@ -24,17 +24,21 @@
 
 use serde::Serialize;
 
-use crate::AnkiRequest;
+use crate::{ids::ReviewId, AnkiRequest};
 
 /// Parameters for the "`getLatestReviewID`" action.
+///
+/// The returned id is the time of the latest review, see [`ReviewId::reviewed_at`], or `0` if
+/// the deck wasn't reviewed yet.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GetLatestReviewIDRequest {
     pub deck: String,
 }
 
 impl AnkiRequest for GetLatestReviewIDRequest {
-    type Response = usize;
+    type Response = ReviewId;
 
     const ACTION: &'static str = "getLatestReviewID";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
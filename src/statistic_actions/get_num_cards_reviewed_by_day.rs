@@ -25,7 +25,10 @@
 use serde::Serialize;
 use serde_tuple::Deserialize_tuple;
 
-use crate::AnkiRequest;
+use crate::{
+    timestamps::{self, CalendarDate},
+    AnkiRequest,
+};
 
 /// Parameters for the "`getNumCardsReviewedByDay`" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
@@ -37,9 +40,18 @@ pub struct GetNumCardsReviewedByDayResponse {
     pub number: usize,
 }
 
+impl GetNumCardsReviewedByDayResponse {
+    /// Parses `date_string`, which is formatted as `YYYY-MM-DD`.
+    #[must_use]
+    pub fn date<D: CalendarDate>(&self) -> Option<D> {
+        timestamps::parse_date(&self.date_string)
+    }
+}
+
 impl AnkiRequest for GetNumCardsReviewedByDayRequest {
     type Response = Vec<GetNumCardsReviewedByDayResponse>;
 
     const ACTION: &'static str = "getNumCardsReviewedByDay";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
@@ -26,32 +26,55 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::AnkiRequest;
+use crate::{
+    ids::{CardId, ReviewId},
+    scheduling::{Ease, ReviewType},
+    timestamps::Timestamp,
+    AnkiRequest,
+};
 
 /// Parameters for the "`getReviewsOfCards`" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GetReviewsOfCardsRequest {
     /// IDs of the cards to get reviews.
-    pub cards: Vec<usize>,
+    pub cards: Vec<CardId>,
 }
 
+/// A review of a card from the review log.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetReviewsOfCardsResponse {
-    id: usize,
-    usn: usize,
-    ease: usize,
-    ivl: isize,
-    last_ivl: isize,
-    factor: usize,
-    time: usize,
+    /// The id of the review, which is its time in milliseconds.
+    pub id: ReviewId,
+    /// The update sequence number.
+    pub usn: isize,
+    /// The button, which was pressed.
+    pub ease: Ease,
+    /// The new interval, which is negative for seconds and positive for days.
+    pub ivl: isize,
+    /// The previous interval, which is negative for seconds and positive for days.
+    pub last_ivl: isize,
+    /// The new ease factor in permille.
+    pub factor: usize,
+    /// The duration of the review in milliseconds.
+    pub time: usize,
+    /// The type of the review.
     #[serde(rename = "type")]
-    type_: usize,
+    pub type_: ReviewType,
+}
+
+impl GetReviewsOfCardsResponse {
+    /// Returns the time of the review, which is stored in its id.
+    #[must_use]
+    pub fn reviewed_at<T: Timestamp>(&self) -> Option<T> {
+        self.id.reviewed_at()
+    }
 }
 
 impl AnkiRequest for GetReviewsOfCardsRequest {
-    type Response = HashMap<String, Vec<GetReviewsOfCardsResponse>>;
+    type Response = HashMap<CardId, Vec<GetReviewsOfCardsResponse>>;
 
     const ACTION: &'static str = "getReviewsOfCards";
     const VERSION: u8 = 6;
+    const IDEMPOTENT: bool = true;
 }
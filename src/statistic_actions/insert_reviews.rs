@@ -25,7 +25,11 @@
 use serde::Serialize;
 use serde_tuple::Serialize_tuple;
 
-use crate::AnkiRequest;
+use crate::{
+    ids::{CardId, ReviewId},
+    scheduling::{Ease, ReviewType},
+    AnkiRequest,
+};
 
 /// Parameters for the "insertReviews" action.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
@@ -36,15 +40,15 @@ pub struct InsertReviewsRequest {
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize_tuple)]
 pub struct InsertReviewsData {
-    review_time: usize,
-    card_id: usize,
+    review_time: ReviewId,
+    card_id: CardId,
     usn: isize,
-    button_pressed: usize,
+    button_pressed: Ease,
     new_interval: isize,
     previous_interval: isize,
     new_factor: usize,
     review_duration: usize,
-    review_type: usize,
+    review_type: ReviewType,
 }
 
 impl AnkiRequest for InsertReviewsRequest {
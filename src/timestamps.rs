@@ -0,0 +1,214 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A point in time, which can be converted from and into the unix timestamps used by Anki.
+///
+/// It is implemented for [`SystemTime`], for `chrono::DateTime<Utc>` with the `chrono` feature
+/// and for `time::OffsetDateTime` with the `time` feature.
+pub trait Timestamp: Sized {
+    /// Converts milliseconds since the unix epoch, returning `None` if they are out of range.
+    fn from_unix_millis(millis: i64) -> Option<Self>;
+
+    /// Returns the milliseconds since the unix epoch.
+    fn unix_millis(&self) -> i64;
+
+    /// Converts seconds since the unix epoch, returning `None` if they are out of range.
+    fn from_unix_seconds(seconds: i64) -> Option<Self> {
+        Self::from_unix_millis(seconds.checked_mul(1000)?)
+    }
+
+    /// Returns the seconds since the unix epoch.
+    fn unix_seconds(&self) -> i64 {
+        self.unix_millis().div_euclid(1000)
+    }
+}
+
+impl Timestamp for SystemTime {
+    fn from_unix_millis(millis: i64) -> Option<Self> {
+        let offset = Duration::from_millis(millis.unsigned_abs());
+        if millis < 0 {
+            UNIX_EPOCH.checked_sub(offset)
+        } else {
+            UNIX_EPOCH.checked_add(offset)
+        }
+    }
+
+    fn unix_millis(&self) -> i64 {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => after.as_millis() as i64,
+            Err(before) => -(before.duration().as_millis() as i64),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn from_unix_millis(millis: i64) -> Option<Self> {
+        Self::from_timestamp_millis(millis)
+    }
+
+    fn unix_millis(&self) -> i64 {
+        self.timestamp_millis()
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn from_unix_millis(millis: i64) -> Option<Self> {
+        Self::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000).ok()
+    }
+
+    fn unix_millis(&self) -> i64 {
+        (self.unix_timestamp_nanos() / 1_000_000) as i64
+    }
+}
+
+/// A calendar date, like the `YYYY-MM-DD` dates of "`getNumCardsReviewedByDay`".
+///
+/// It is implemented for `chrono::NaiveDate` with the `chrono` feature and for `time::Date`
+/// with the `time` feature.
+pub trait CalendarDate: Sized {
+    /// Creates the date, returning `None` if it doesn't exist.
+    fn from_ymd(year: i32, month: u8, day: u8) -> Option<Self>;
+}
+
+#[cfg(feature = "chrono")]
+impl CalendarDate for chrono::NaiveDate {
+    fn from_ymd(year: i32, month: u8, day: u8) -> Option<Self> {
+        Self::from_ymd_opt(year, month.into(), day.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl CalendarDate for time::Date {
+    fn from_ymd(year: i32, month: u8, day: u8) -> Option<Self> {
+        Self::from_calendar_date(year, month.try_into().ok()?, day).ok()
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date, returning `None` if it is malformed.
+pub fn parse_date<D: CalendarDate>(date: &str) -> Option<D> {
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    D::from_ymd(year, month, day)
+}
+
+/// Serde adapter for timestamps, which are serialized as milliseconds since the unix epoch.
+///
+/// ```
+/// use std::time::SystemTime;
+///
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Review {
+///     #[serde(with = "anki_bridge::timestamps::millis")]
+///     id: SystemTime,
+/// }
+/// ```
+pub mod millis {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::Timestamp;
+
+    pub fn serialize<T: Timestamp, S: Serializer>(
+        timestamp: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(timestamp.unix_millis())
+    }
+
+    pub fn deserialize<'de, T: Timestamp, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let millis = i64::deserialize(deserializer)?;
+        T::from_unix_millis(millis)
+            .ok_or_else(|| D::Error::custom(format!("timestamp {millis} is out of range")))
+    }
+}
+
+/// Serde adapter for timestamps, which are serialized as seconds since the unix epoch, like the
+/// `mod` fields.
+pub mod seconds {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::Timestamp;
+
+    pub fn serialize<T: Timestamp, S: Serializer>(
+        timestamp: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(timestamp.unix_seconds())
+    }
+
+    pub fn deserialize<'de, T: Timestamp, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let seconds = i64::deserialize(deserializer)?;
+        T::from_unix_seconds(seconds)
+            .ok_or_else(|| D::Error::custom(format!("timestamp {seconds} is out of range")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_time() {
+        let time = SystemTime::from_unix_millis(1_700_000_000_123).unwrap();
+        assert_eq!(time.unix_millis(), 1_700_000_000_123);
+        assert_eq!(time.unix_seconds(), 1_700_000_000);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        use chrono::{DateTime, NaiveDate, Utc};
+
+        let time = DateTime::<Utc>::from_unix_seconds(1_700_000_000).unwrap();
+        assert_eq!(time.to_rfc3339(), "2023-11-14T22:13:20+00:00");
+        assert_eq!(
+            parse_date::<NaiveDate>("2023-11-14"),
+            NaiveDate::from_ymd_opt(2023, 11, 14)
+        );
+        assert_eq!(parse_date::<NaiveDate>("2023-02-30"), None);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time() {
+        use time::{Date, Month, OffsetDateTime};
+
+        let time = OffsetDateTime::from_unix_millis(1_700_000_000_123).unwrap();
+        assert_eq!(time.unix_millis(), 1_700_000_000_123);
+        assert_eq!(
+            parse_date::<Date>("2023-11-14"),
+            Date::from_calendar_date(2023, Month::November, 14).ok()
+        );
+    }
+}
@@ -0,0 +1,345 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    transport::{AsyncTransport, Transport, TransportRequest},
+    Error, Result,
+};
+
+/// A recorded request and its response, which is stored as one line of a JSONL cassette.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteEntry {
+    /// The envelope of the request without the API key.
+    pub request: Value,
+    /// The response as JSON, or as a string if it wasn't valid JSON.
+    pub response: Value,
+}
+
+impl CassetteEntry {
+    fn new(request: &Value, response: &str) -> Self {
+        let mut request = request.clone();
+        if let Some(envelope) = request.as_object_mut() {
+            envelope.remove("key");
+        }
+        Self {
+            request,
+            response: serde_json::from_str(response)
+                .unwrap_or_else(|_| Value::String(response.to_string())),
+        }
+    }
+
+    /// Returns [true] if the envelope has the same action and params as the recorded request.
+    fn matches(&self, envelope: &Value) -> bool {
+        let params = |value: &Value| value.get("params").cloned().unwrap_or(Value::Null);
+        self.request.get("action") == envelope.get("action")
+            && params(&self.request) == params(envelope)
+    }
+
+    fn response_body(&self) -> String {
+        match &self.response {
+            Value::String(body) => body.clone(),
+            response => response.to_string(),
+        }
+    }
+}
+
+/// Wraps a transport and appends every request and its response to a cassette, which can be
+/// replayed by the [`ReplayTransport`]. The API key isn't recorded.
+///
+/// A request, which was sent successfully, isn't failed if it can't be recorded, as the action
+/// already ran and repeating it could apply it twice. The first recording failure is kept instead
+/// and returned by [`RecordingTransport::finish`].
+#[derive(Debug, Clone)]
+pub struct RecordingTransport<T> {
+    pub inner: T,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+#[derive(Debug)]
+struct Cassette {
+    file: File,
+    /// The first error, which occurred while recording.
+    error: Option<Error>,
+}
+
+impl<T> RecordingTransport<T> {
+    /// Creates the cassette, an existing cassette is truncated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created.
+    pub fn create(inner: T, path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::with_file(inner, File::create(path)?))
+    }
+
+    /// Opens the cassette to append further entries, it is created if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be opened.
+    pub fn append(inner: T, path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::with_file(inner, file))
+    }
+
+    fn with_file(inner: T, file: File) -> Self {
+        Self {
+            inner,
+            cassette: Arc::new(Mutex::new(Cassette { file, error: None })),
+        }
+    }
+
+    fn cassette(&self) -> MutexGuard<'_, Cassette> {
+        self.cassette
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Flushes the cassette and returns the first error, which occurred while recording. The
+    /// error is cleared, so recording can continue afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry couldn't be recorded or the cassette can't be flushed.
+    pub fn finish(&self) -> Result<()> {
+        let mut cassette = self.cassette();
+        if let Some(error) = cassette.error.take() {
+            return Err(error);
+        }
+        cassette.file.flush()?;
+        Ok(())
+    }
+
+    fn record(&self, request: &Value, response: &str) {
+        let mut cassette = self.cassette();
+        let result = serde_json::to_string(&CassetteEntry::new(request, response))
+            .map_err(|error| Error::Transport(Box::new(error)))
+            .and_then(|line| Ok(writeln!(cassette.file, "{line}")?));
+        if let Err(error) = result {
+            cassette.error.get_or_insert(error);
+        }
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+        let response = self.inner.send(request)?;
+        self.record(request.body, &response);
+        Ok(response)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: AsyncTransport> AsyncTransport for RecordingTransport<T> {
+    async fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+        let response = self.inner.send(request).await?;
+        self.record(request.body, &response);
+        Ok(response)
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        self.inner.sleep(duration).await;
+    }
+}
+
+/// Serves the responses of a cassette, which was recorded by the [`RecordingTransport`].
+///
+/// A request is answered by the first entry with the same action and params, which wasn't
+/// replayed yet, so a repeated request gets the responses in the recorded order. A request
+/// without such an entry fails with an [`Error::Transport`].
+#[derive(Debug, Clone, Default)]
+pub struct ReplayTransport {
+    entries: Arc<Mutex<Vec<(CassetteEntry, bool)>>>,
+}
+
+impl ReplayTransport {
+    /// Reads the cassette.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or a line isn't a valid entry.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut entries = Vec::new();
+        for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|error| {
+                Error::Transport(
+                    format!(
+                        "invalid entry in line {} of {}: {error}",
+                        number + 1,
+                        path.display()
+                    )
+                    .into(),
+                )
+            })?;
+            entries.push(entry);
+        }
+        Ok(Self::from_entries(entries))
+    }
+
+    #[must_use]
+    pub fn from_entries(entries: Vec<CassetteEntry>) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(
+                entries.into_iter().map(|entry| (entry, false)).collect(),
+            )),
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Vec<(CassetteEntry, bool)>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Returns the entries, which weren't replayed yet.
+    #[must_use]
+    pub fn remaining(&self) -> Vec<CassetteEntry> {
+        self.entries()
+            .iter()
+            .filter(|(_, replayed)| !replayed)
+            .map(|(entry, _)| entry.clone())
+            .collect()
+    }
+
+    fn replay(&self, envelope: &Value) -> Result<String> {
+        let mut entries = self.entries();
+        let (entry, replayed) = entries
+            .iter_mut()
+            .find(|(entry, replayed)| !replayed && entry.matches(envelope))
+            .ok_or_else(|| {
+                Error::Transport(format!("no recorded response for the request {envelope}").into())
+            })?;
+        *replayed = true;
+        Ok(entry.response_body())
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+        self.replay(request.body)
+    }
+}
+
+/// The asynchronous variant of the [`ReplayTransport`].
+#[derive(Debug, Clone, Default)]
+pub struct AsyncReplayTransport(pub ReplayTransport);
+
+#[async_trait::async_trait]
+impl AsyncTransport for AsyncReplayTransport {
+    async fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+        self.0.replay(request.body)
+    }
+
+    async fn sleep(&self, _duration: Duration) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fake::FakeAnki, prelude::*, AnkiClient};
+
+    use super::*;
+
+    /// Answers the requests with a fake instead of sending them over HTTP.
+    struct FakeTransport(FakeAnki);
+
+    impl Transport for FakeTransport {
+        fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+            Ok(self.0.handle_envelope(request.body).to_string())
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let path =
+            std::env::temp_dir().join(format!("anki_bridge_cassette_{}.jsonl", std::process::id()));
+        let transport = RecordingTransport::create(FakeTransport(FakeAnki::new()), &path).unwrap();
+        let client = AnkiClient::with_transport("", transport).with_key("secret");
+        let create = CreateDeckRequest {
+            deck: "Japanese".to_string(),
+        };
+        let recorded_id = client.request(create.clone()).unwrap();
+        let recorded_decks = client.request(DeckNamesRequest {}).unwrap();
+        client.transport.finish().unwrap();
+
+        let transport = ReplayTransport::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(transport
+            .remaining()
+            .iter()
+            .all(|entry| entry.request.get("key").is_none()));
+        let client = AnkiClient::with_transport("", transport.clone());
+        assert_eq!(client.request(DeckNamesRequest {}).unwrap(), recorded_decks);
+        assert_eq!(client.request(create.clone()).unwrap(), recorded_id);
+        assert!(transport.remaining().is_empty());
+        let error = client.request(create).unwrap_err();
+        assert_eq!(error.action(), Some("createDeck"));
+        assert!(matches!(
+            error,
+            Error::Request { source, .. } if matches!(*source, Error::Transport(_))
+        ));
+    }
+
+    #[test]
+    fn test_recording_failure_keeps_the_response() {
+        let path = std::env::temp_dir().join(format!(
+            "anki_bridge_readonly_cassette_{}.jsonl",
+            std::process::id()
+        ));
+        File::create(&path).unwrap();
+        // A file opened for reading only can't be written to.
+        let file = File::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let anki = FakeAnki::new();
+        let transport = RecordingTransport::with_file(FakeTransport(anki.clone()), file);
+        let client = AnkiClient::with_transport("", transport);
+
+        let id = client
+            .request(CreateDeckRequest {
+                deck: "Japanese".to_string(),
+            })
+            .unwrap();
+        assert_eq!(
+            anki.request(DeckNamesAndIdsRequest {}).unwrap()["Japanese"],
+            id
+        );
+        assert!(matches!(client.transport.finish(), Err(Error::Io(_))));
+        assert!(client.transport.finish().is_ok());
+    }
+}
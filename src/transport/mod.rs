@@ -0,0 +1,105 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::Result;
+
+/// Transports which record requests to a cassette and replay them in tests.
+pub mod cassette;
+/// Transport which sends requests with `reqwest`'s asynchronous client.
+#[cfg(feature = "reqwest_async")]
+pub mod reqwest_async;
+/// Transport which sends requests with `reqwest`'s blocking client.
+#[cfg(feature = "reqwest_blocking")]
+pub mod reqwest_blocking;
+/// Transport which sends requests with `ureq`.
+#[cfg(feature = "ureq_blocking")]
+pub mod ureq_blocking;
+
+pub use cassette::{AsyncReplayTransport, CassetteEntry, RecordingTransport, ReplayTransport};
+#[cfg(feature = "reqwest_async")]
+pub use reqwest_async::ReqwestAsyncTransport;
+#[cfg(feature = "reqwest_blocking")]
+pub use reqwest_blocking::ReqwestBlockingTransport;
+#[cfg(feature = "ureq_blocking")]
+pub use ureq_blocking::UreqTransport;
+
+/// A request, which is sent by a transport to `AnkiConnect`.
+#[derive(Debug, Clone, Copy)]
+pub struct TransportRequest<'a> {
+    /// The URL of `AnkiConnect`.
+    pub endpoint: &'a str,
+    /// Headers, which have to be sent with the request.
+    pub headers: &'a [(String, String)],
+    /// The JSON envelope of the request.
+    pub body: &'a Value,
+}
+
+/// Sends requests to `AnkiConnect` and blocks until the response is received.
+pub trait Transport: Send + Sync {
+    /// Posts the body to the endpoint and returns the raw body of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request can't be sent or the response can't be read.
+    fn send(&self, request: TransportRequest<'_>) -> Result<String>;
+}
+
+/// Sends requests to `AnkiConnect` asynchronously.
+#[async_trait::async_trait]
+pub trait AsyncTransport: Send + Sync {
+    /// Posts the body to the endpoint and returns the raw body of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request can't be sent or the response can't be read.
+    async fn send(&self, request: TransportRequest<'_>) -> Result<String>;
+
+    /// Waits for the given duration before a request is retried, using the runtime of the
+    /// transport.
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Options for transports, which are built by the
+/// [`AnkiClientBuilder`](crate::AnkiClientBuilder).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TransportOptions {
+    /// The timeout of a whole request, including connecting and reading the response.
+    pub timeout: Option<Duration>,
+    /// The `User-Agent` header.
+    pub user_agent: Option<String>,
+}
+
+/// Transports which can be built from [`TransportOptions`].
+pub trait BuildTransport: Sized {
+    /// Builds the transport.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying HTTP client can't be built.
+    fn build(options: &TransportOptions) -> Result<Self>;
+}
@@ -0,0 +1,63 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::{
+    transport::{BuildTransport, Transport, TransportOptions, TransportRequest},
+    Result,
+};
+
+/// Sends requests with a [`reqwest::blocking::Client`].
+#[derive(Default, Debug, Clone)]
+pub struct ReqwestBlockingTransport {
+    pub client: reqwest::blocking::Client,
+}
+
+impl BuildTransport for ReqwestBlockingTransport {
+    fn build(options: &TransportOptions) -> Result<Self> {
+        let mut builder = reqwest::blocking::Client::builder();
+        if let Some(timeout) = options.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(user_agent) = &options.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        Ok(Self {
+            client: builder.build()?,
+        })
+    }
+}
+
+impl Transport for ReqwestBlockingTransport {
+    fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+        Ok(request
+            .headers
+            .iter()
+            .fold(self.client.post(request.endpoint), |http, (name, value)| {
+                http.header(name, value)
+            })
+            .json(request.body)
+            .send()?
+            .text()?)
+    }
+}
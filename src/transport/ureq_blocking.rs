@@ -0,0 +1,146 @@
+/*
+* The MIT License (MIT)
+*
+* Copyright (c) 2023 Daniél Kerkmann <daniel@kerkmann.dev>
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use std::io::Read;
+
+use crate::{
+    transport::{BuildTransport, Transport, TransportOptions, TransportRequest},
+    Error, Result,
+};
+
+/// Sends requests with a [`ureq::Agent`].
+#[derive(Debug, Clone)]
+pub struct UreqTransport {
+    pub agent: ureq::Agent,
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self {
+            agent: ureq::agent(),
+        }
+    }
+}
+
+impl BuildTransport for UreqTransport {
+    fn build(options: &TransportOptions) -> Result<Self> {
+        let mut builder = ureq::AgentBuilder::new();
+        if let Some(timeout) = options.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(user_agent) = &options.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        Ok(Self {
+            agent: builder.build(),
+        })
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(&self, request: TransportRequest<'_>) -> Result<String> {
+        let mut body = String::new();
+        request
+            .headers
+            .iter()
+            .fold(self.agent.post(request.endpoint), |http, (name, value)| {
+                http.set(name, value)
+            })
+            .send_json(request.body)
+            .map_err(|error| Error::Ureq(Box::new(error)))?
+            .into_reader()
+            .read_to_string(&mut body)?;
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use crate::{prelude::*, Error};
+
+    /// Answers a single request and returns its lowercased head.
+    fn answer_once(listener: TcpListener) -> thread::JoinHandle<String> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            while reader.read_line(&mut head).unwrap() > 2 {}
+            let body = r#"{"result": [], "error": null}"#;
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            head.to_lowercase()
+        })
+    }
+
+    #[test]
+    fn test_builder_options_are_sent() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = answer_once(listener);
+        let client = UreqClient::builder()
+            .endpoint(endpoint)
+            .user_agent("my-app/1.0")
+            .header("X-Request-Source", "tests")
+            .build()
+            .unwrap();
+        client.request(DeckNamesRequest).unwrap();
+        let head = server.join().unwrap();
+        assert!(head.contains("\r\nuser-agent: my-app/1.0\r\n"), "{head}");
+        assert!(head.contains("\r\nx-request-source: tests\r\n"), "{head}");
+    }
+
+    #[test]
+    fn test_builder_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let client = UreqClient::builder()
+            .endpoint(endpoint)
+            .timeout(Duration::from_millis(100))
+            .build()
+            .unwrap();
+        let start = Instant::now();
+        let result = client.request(DeckNamesRequest);
+        assert!(
+            matches!(
+                &result,
+                Err(Error::Request { action: "deckNames", source })
+                    if matches!(**source, Error::Ureq(_))
+            ),
+            "{result:?}"
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(listener);
+    }
+}